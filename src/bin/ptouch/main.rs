@@ -0,0 +1,412 @@
+use std::{
+  path::{Path, PathBuf},
+  time::Duration,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ptouch_rs::{
+  Align,
+  MediaType,
+  OpenOptions,
+  PrintOptions,
+  PrinterDefinition,
+  PrinterFlags,
+  PrinterType,
+  TapeCounter,
+  TapeSize,
+  Timeouts,
+  TwoColor,
+  blocking::Printer,
+  report::{DeviceReport, PrinterReport, Report},
+};
+
+mod text;
+
+/// Print labels on Brother P-Touch label makers
+#[derive(Debug, Parser)]
+#[command(name = "ptouch", version)]
+struct Cli {
+  /// Print machine-readable JSON instead of text
+  #[arg(long, global = true)]
+  json: bool,
+  /// Give up on connecting to a printer, or on a transfer, after this many
+  /// seconds
+  #[arg(long, global = true, value_name = "SECONDS", value_parser = parse_seconds)]
+  timeout: Option<Duration>,
+  /// Add the tape used by each print to the per-printer totals in this file
+  #[arg(long, global = true, value_name = "FILE")]
+  counter: Option<PathBuf>,
+  /// Print over the network to the printer at this address, such as
+  /// 10.0.0.5:9100, instead of over USB
+  #[arg(long, global = true, value_name = "ADDRESS")]
+  host: Option<String>,
+  /// Print to the printer on this serial port, such as /dev/rfcomm0,
+  /// instead of over USB
+  #[cfg(unix)]
+  #[arg(
+    long,
+    global = true,
+    value_name = "DEVICE",
+    conflicts_with_all = ["host", "output"]
+  )]
+  serial: Option<PathBuf>,
+  /// Write the print job to this file instead of sending it to a printer
+  #[arg(
+    long,
+    global = true,
+    value_name = "FILE",
+    requires = "model",
+    conflicts_with = "host"
+  )]
+  output: Option<PathBuf>,
+  /// Printer to write the print job for, such as PT-P700, or to expect at
+  /// the other end of --host or --serial
+  #[arg(long, global = true, value_name = "MODEL")]
+  model: Option<String>,
+  /// Width of the tape to assume when writing the print job, in millimetres
+  #[arg(long, global = true, value_name = "MM", default_value_t = 12)]
+  tape: u8,
+  /// Register the printers defined in this TOML file
+  #[arg(long, global = true, value_name = "FILE")]
+  models: Option<PathBuf>,
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+  /// Show the capabilities of the connected printer
+  Info,
+  /// Show the status of the connected printer and its tape
+  Status,
+  /// List all connected printers
+  List,
+  /// Print an image
+  Print {
+    image: PathBuf,
+    #[command(flatten)]
+    options: PrintArgs,
+  },
+  /// Print one or more lines of text
+  Text {
+    #[arg(required = true)]
+    lines: Vec<String>,
+    /// TrueType or OpenType font to render the text with
+    #[arg(long)]
+    font: PathBuf,
+    #[command(flatten)]
+    options: PrintArgs,
+  },
+  /// Estimate the tape used by printing images
+  Usage {
+    #[arg(required = true)]
+    images: Vec<PathBuf>,
+    #[command(flatten)]
+    options: PrintArgs,
+  },
+  /// Cut the tape
+  Cut,
+  /// Feed blank tape
+  Feed {
+    /// Length to feed, in millimetres
+    #[arg(default_value_t = 5.0)]
+    mm: f32,
+  },
+}
+
+#[derive(Debug, Args)]
+struct PrintArgs {
+  /// Number of copies to print
+  #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+  copies: u32,
+  /// Skip the final feed and cut, so the next label follows on directly
+  #[arg(long)]
+  chain: bool,
+  /// Cut the tape before printing
+  #[arg(long)]
+  precut: bool,
+  /// Dither greyscale images instead of thresholding them
+  #[arg(long)]
+  dither: bool,
+  /// Make each label exactly this long, in millimetres
+  #[arg(long, value_name = "MM")]
+  length: Option<f32>,
+  /// Where to put the image on a label longer than it
+  #[arg(long, value_enum, default_value_t = AlignArg::Start)]
+  align: AlignArg,
+  /// Print at double resolution along the tape, on printers that support it
+  #[arg(long)]
+  high_resolution: bool,
+  /// Print red parts of the image in red, on printers and media that support it
+  #[arg(long)]
+  two_color: bool,
+  /// Lightest luma, from 0 to 255, that still prints black in two-colour mode
+  #[arg(long, value_name = "LUMA", default_value_t = TwoColor::default().black)]
+  black_threshold: u8,
+  /// How far red must exceed green and blue, from 0 to 255, to print red
+  #[arg(long, value_name = "AMOUNT", default_value_t = TwoColor::default().red)]
+  red_threshold: u8,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AlignArg {
+  Start,
+  Centre,
+  End,
+}
+
+impl From<AlignArg> for Align {
+  fn from(align: AlignArg) -> Self {
+    match align {
+      AlignArg::Start => Align::Start,
+      AlignArg::Centre => Align::Centre,
+      AlignArg::End => Align::End,
+    }
+  }
+}
+
+impl From<PrintArgs> for PrintOptions {
+  fn from(args: PrintArgs) -> Self {
+    PrintOptions {
+      copies: args.copies,
+      chain: args.chain,
+      precut: args.precut,
+      dither: args.dither,
+      length: args.length,
+      align: args.align.into(),
+      high_resolution: args.high_resolution,
+      two_color: args.two_color.then_some(TwoColor {
+        black: args.black_threshold,
+        red: args.red_threshold,
+      }),
+    }
+  }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  let cli = Cli::parse();
+
+  let mut custom = Vec::new();
+
+  if let Some(models) = &cli.models {
+    for definition in PrinterDefinition::from_toml(&std::fs::read_to_string(models)?)? {
+      let name = definition.name.clone();
+
+      custom.push((name, PrinterType::register(definition)?));
+    }
+  }
+
+  let mut options = OpenOptions::default();
+
+  if let Some(timeout) = cli.timeout {
+    options.timeouts = Timeouts {
+      connect: Some(timeout),
+      send: Some(timeout),
+      receive: Some(timeout),
+    };
+  }
+
+  if let Some(model) = &cli.model {
+    let Some(ty) = find_model(&custom, model) else {
+      Err(format!("unknown printer model {model}"))?
+    };
+
+    options.model = Some(ty);
+  }
+
+  let open = || {
+    if let (Some(output), Some(ty)) = (&cli.output, options.model) {
+      let media_type = if ty.info().flags.contains(PrinterFlags::QlRaster) {
+        MediaType::Continuous
+      } else {
+        MediaType::Laminated
+      };
+
+      return Ok(Printer::create_with(
+        output,
+        ty,
+        TapeSize::from_media(cli.tape, media_type),
+        options.clone(),
+      )?);
+    }
+
+    #[cfg(unix)]
+    if let Some(serial) = &cli.serial {
+      return Ok(Printer::open_serial_with(serial, options.clone())?);
+    }
+
+    let printer = match &cli.host {
+      Some(host) => Printer::connect_with(host.as_str(), options.clone())?,
+      None => Printer::open_with(options.clone())?,
+    };
+
+    Ok::<_, Box<dyn std::error::Error>>(printer)
+  };
+
+  match cli.command {
+    Command::List if cli.json => {
+      let printers = Printer::list()?
+        .iter()
+        .map(DeviceReport::from)
+        .collect::<Vec<_>>();
+
+      println!("{}", serde_json::to_string_pretty(&printers)?);
+    },
+    Command::List => {
+      for printer in Printer::list()? {
+        print!(
+          "{:?} (bus {}, address {}",
+          printer.ty, printer.bus_number, printer.device_address
+        );
+
+        if let Some(serial) = printer.serial_number {
+          print!(", serial {serial}");
+        }
+
+        println!(")");
+      }
+    },
+    Command::Info if cli.json => {
+      let printer = open()?;
+
+      println!(
+        "{}",
+        serde_json::to_string_pretty(&PrinterReport::from(printer.ty()))?
+      );
+    },
+    Command::Info => {
+      let printer = open()?;
+      let info = printer.ty().info();
+
+      match printer.ty().definition() {
+        Some(definition) => println!("Printer: {}", definition.name),
+        None => println!("Printer: {:?}", printer.ty()),
+      }
+
+      println!("USB ID: {:04x}:{:04x}", info.vendor_id, info.product_id);
+      println!("Resolution: {} dpi", info.dpi);
+      println!("Print head: {} px", info.max_px);
+      println!("Flags: {:?}", info.flags);
+    },
+    Command::Status if cli.json => {
+      let printer = open()?;
+
+      println!(
+        "{}",
+        serde_json::to_string_pretty(&Report::from(printer.as_async()))?
+      );
+    },
+    Command::Status => {
+      let printer = open()?;
+      let status = printer.status();
+
+      println!("Status: {}", status.status_type.name());
+
+      if let Some(model) = status.printer_type() {
+        println!("Reported model: {model:?}");
+      }
+
+      println!("Phase: {}", status.phase.name());
+
+      for error in status.error.iter() {
+        println!("Error: {}", error.name());
+      }
+
+      println!("Media: {}", status.media_type.name());
+      println!("Tape width: {} mm", status.media_width.mm());
+      println!("Tape colour: {}", status.tape_color.name());
+      println!("Text colour: {}", status.text_color.name());
+    },
+    Command::Print { image, options } => {
+      let printer = open()?;
+      let image = image::open(image)?;
+
+      print(&printer, image, options.into(), cli.counter.as_deref())?;
+    },
+    Command::Text {
+      lines,
+      font,
+      options,
+    } => {
+      let printer = open()?;
+      let height = printer.tape_info().px;
+      let image = text::render(&font, &lines, height)?;
+
+      print(&printer, image, options.into(), cli.counter.as_deref())?;
+    },
+    Command::Usage { images, options } => {
+      let printer = open()?;
+      let images = images
+        .into_iter()
+        .map(image::open)
+        .collect::<Result<Vec<_>, _>>()?;
+
+      let usage = printer.tape_usage(&images, &options.into())?;
+
+      if cli.json {
+        println!("{}", serde_json::to_string_pretty(&usage)?);
+      } else {
+        println!("Labels: {}", usage.labels);
+        println!("Printed: {:.1} mm", usage.printed);
+        println!("Waste: {:.1} mm", usage.waste);
+        println!("Total: {:.1} mm", usage.total());
+      }
+    },
+    Command::Cut => {
+      let printer = open()?;
+
+      printer.cut()?;
+    },
+    Command::Feed { mm } => {
+      let printer = open()?;
+      let lines = printer.ty().info().lines(mm);
+
+      printer.feed(lines)?;
+    },
+  }
+
+  Ok(())
+}
+
+/// Looks up a printer by name, such as PT-P700 or QL-800, including the
+/// custom ones that were registered.
+/// A number of seconds, which has to be finite and not negative.
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+  let seconds = seconds.parse::<f32>().map_err(|e| e.to_string())?;
+
+  Duration::try_from_secs_f32(seconds).map_err(|e| e.to_string())
+}
+
+fn find_model(custom: &[(String, PrinterType)], name: &str) -> Option<PrinterType> {
+  let custom = custom
+    .iter()
+    .find(|(custom, _)| custom.eq_ignore_ascii_case(name))
+    .map(|(_, ty)| *ty);
+
+  custom.or_else(|| {
+    PrinterType::iter().find(|ty| ty.name().eq_ignore_ascii_case(&name.replace('-', "_")))
+  })
+}
+
+fn print(
+  printer: &Printer,
+  image: image::DynamicImage,
+  options: PrintOptions,
+  counter: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let length = printer.label_length(&image, &options)?;
+  eprintln!("Label length: {length:.1} mm");
+
+  let usage = printer.tape_usage(std::slice::from_ref(&image), &options)?;
+
+  printer.print_with(image, &options)?;
+
+  if let Some(counter) = counter {
+    match printer.serial_number() {
+      Some(serial) => TapeCounter::open(counter)?.record(serial, &usage)?,
+      None => eprintln!("Printer has no serial number, not counting tape used"),
+    }
+  }
+
+  Ok(())
+}
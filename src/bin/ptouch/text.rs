@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use image::{DynamicImage, GrayImage, Luma};
+
+/// Renders each line of text in black on white, splitting `height` pixels
+/// evenly between the lines.
+pub fn render(
+  font: &Path,
+  lines: &[String],
+  height: u32,
+) -> Result<DynamicImage, Box<dyn std::error::Error>> {
+  let font = FontVec::try_from_vec(std::fs::read(font)?)?;
+
+  let line_height = height / lines.len().max(1) as u32;
+  let font = font.as_scaled(PxScale::from(line_height as f32));
+
+  let widths = lines
+    .iter()
+    .map(|line| line_width(&font, line))
+    .collect::<Vec<_>>();
+  let width = widths.iter().copied().fold(0.0, f32::max).ceil() as u32;
+
+  let mut image = GrayImage::from_pixel(width.max(1), height.max(1), Luma([0xff]));
+
+  for (i, line) in lines.iter().enumerate() {
+    let baseline = (i as u32 * line_height) as f32 + font.ascent();
+    let mut x = (width as f32 - widths[i]) / 2.0;
+    let mut previous = None;
+
+    for c in line.chars() {
+      let id = font.glyph_id(c);
+
+      if let Some(previous) = previous {
+        x += font.kern(previous, id);
+      }
+
+      let glyph = id.with_scale_and_position(font.scale(), point(x, baseline));
+      x += font.h_advance(id);
+      previous = Some(id);
+
+      let Some(outline) = font.outline_glyph(glyph) else {
+        continue;
+      };
+
+      let bounds = outline.px_bounds();
+
+      outline.draw(|gx, gy, coverage| {
+        let x = bounds.min.x as i32 + gx as i32;
+        let y = bounds.min.y as i32 + gy as i32;
+
+        if coverage > 0.5 && x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+          image.put_pixel(x as u32, y as u32, Luma([0x00]));
+        }
+      });
+    }
+  }
+
+  Ok(DynamicImage::ImageLuma8(image))
+}
+
+fn line_width<F: Font>(font: &impl ScaleFont<F>, line: &str) -> f32 {
+  let mut width = 0.0;
+  let mut previous = None;
+
+  for c in line.chars() {
+    let id = font.glyph_id(c);
+
+    if let Some(previous) = previous {
+      width += font.kern(previous, id);
+    }
+
+    width += font.h_advance(id);
+    previous = Some(id);
+  }
+
+  width
+}
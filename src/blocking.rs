@@ -0,0 +1,174 @@
+//! A synchronous [`Printer`], for programs that don't otherwise need an
+//! async runtime.
+//!
+//! There are no blocking transfers underneath: each method runs the async
+//! [`crate::Printer`] method of the same name to completion on the calling
+//! thread with [`futures_lite::future::block_on`]. That's enough because
+//! USB transfers are completed by the operating system, and network and
+//! serial I/O by async-io's own reactor thread, rather than by an executor.
+
+use futures_lite::future::block_on;
+use image::{DynamicImage, RgbImage};
+
+use crate::{
+  ConnectedPrinter,
+  Error,
+  OpenOptions,
+  PrintOptions,
+  PrinterType,
+  Quirks,
+  Status,
+  TapeInfo,
+  TapeSize,
+  TapeUsage,
+};
+
+#[derive(Debug)]
+pub struct Printer {
+  inner: crate::Printer,
+}
+
+impl Printer {
+  pub fn list() -> Result<Vec<ConnectedPrinter>, Error> {
+    crate::Printer::list()
+  }
+
+  pub fn open() -> Result<Self, Error> {
+    block_on(crate::Printer::open()).map(Printer::from)
+  }
+
+  pub fn open_with(options: OpenOptions) -> Result<Self, Error> {
+    block_on(crate::Printer::open_with(options)).map(Printer::from)
+  }
+
+  #[cfg(feature = "network")]
+  pub fn connect(addr: impl std::net::ToSocketAddrs) -> Result<Self, Error> {
+    block_on(crate::Printer::connect(addr)).map(Printer::from)
+  }
+
+  #[cfg(feature = "network")]
+  pub fn connect_with(
+    addr: impl std::net::ToSocketAddrs,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    block_on(crate::Printer::connect_with(addr, options)).map(Printer::from)
+  }
+
+  #[cfg(all(feature = "serial", unix))]
+  pub fn open_serial(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+    block_on(crate::Printer::open_serial(path)).map(Printer::from)
+  }
+
+  #[cfg(all(feature = "serial", unix))]
+  pub fn open_serial_with(
+    path: impl AsRef<std::path::Path>,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    block_on(crate::Printer::open_serial_with(path, options)).map(Printer::from)
+  }
+
+  pub fn write_to(
+    writer: impl std::io::Write + Send + 'static,
+    ty: PrinterType,
+    tape: TapeSize,
+  ) -> Result<Self, Error> {
+    block_on(crate::Printer::write_to(writer, ty, tape)).map(Printer::from)
+  }
+
+  pub fn write_to_with(
+    writer: impl std::io::Write + Send + 'static,
+    ty: PrinterType,
+    tape: TapeSize,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    block_on(crate::Printer::write_to_with(writer, ty, tape, options)).map(Printer::from)
+  }
+
+  pub fn create(
+    path: impl AsRef<std::path::Path>,
+    ty: PrinterType,
+    tape: TapeSize,
+  ) -> Result<Self, Error> {
+    block_on(crate::Printer::create(path, ty, tape)).map(Printer::from)
+  }
+
+  pub fn create_with(
+    path: impl AsRef<std::path::Path>,
+    ty: PrinterType,
+    tape: TapeSize,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    block_on(crate::Printer::create_with(path, ty, tape, options)).map(Printer::from)
+  }
+
+  pub fn reload_status(&mut self) -> Result<Status, Error> {
+    block_on(self.inner.reload_status())
+  }
+
+  pub fn ty(&self) -> PrinterType {
+    self.inner.ty()
+  }
+
+  pub fn status(&self) -> Status {
+    self.inner.status()
+  }
+
+  pub fn quirks(&self) -> Quirks {
+    self.inner.quirks()
+  }
+
+  pub fn serial_number(&self) -> Option<&str> {
+    self.inner.serial_number()
+  }
+
+  pub fn tape_info(&self) -> TapeInfo {
+    self.inner.tape_info()
+  }
+
+  pub fn preview(&self, image: &DynamicImage) -> RgbImage {
+    self.inner.preview(image)
+  }
+
+  pub fn label_length(&self, image: &DynamicImage, options: &PrintOptions) -> Result<f32, Error> {
+    self.inner.label_length(image, options)
+  }
+
+  pub fn tape_usage(
+    &self,
+    images: &[DynamicImage],
+    options: &PrintOptions,
+  ) -> Result<TapeUsage, Error> {
+    self.inner.tape_usage(images, options)
+  }
+
+  pub fn print(&self, image: DynamicImage) -> Result<(), Error> {
+    block_on(self.inner.print(image))
+  }
+
+  pub fn print_with(&self, image: DynamicImage, options: &PrintOptions) -> Result<(), Error> {
+    block_on(self.inner.print_with(image, options))
+  }
+
+  pub fn feed(&self, lines: u32) -> Result<(), Error> {
+    block_on(self.inner.feed(lines))
+  }
+
+  pub fn cut(&self) -> Result<(), Error> {
+    block_on(self.inner.cut())
+  }
+
+  /// The underlying async printer, for use with [`crate::Commands`].
+  pub fn as_async(&self) -> &crate::Printer {
+    &self.inner
+  }
+
+  pub fn into_async(self) -> crate::Printer {
+    self.inner
+  }
+}
+
+impl From<crate::Printer> for Printer {
+  fn from(inner: crate::Printer) -> Self {
+    Printer { inner }
+  }
+}
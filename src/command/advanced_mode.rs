@@ -0,0 +1,65 @@
+use crate::{Error, Printer};
+
+pub struct AdvancedMode;
+
+impl AdvancedMode {
+  pub async fn message(
+    printer: &Printer,
+    chain: bool,
+    high_resolution: bool,
+    two_color: bool,
+  ) -> Result<(), Error> {
+    let mode = Self::mode(chain, high_resolution, two_color);
+
+    printer.send([0x1b, 0x69, 0x4b, mode]).await
+  }
+
+  fn mode(chain: bool, high_resolution: bool, two_color: bool) -> u8 {
+    let mut mode = 0x00;
+
+    // Bit 6 doubles the resolution along the feed direction.
+    if high_resolution {
+      mode |= 0x40;
+    }
+
+    // Bit 3 feeds and cuts after the last label. With it clear, the label
+    // stays under the head for the next job to follow on from.
+    if !chain {
+      mode |= 0x08;
+    }
+
+    // Bit 0 takes a black and a red plane for every line.
+    if two_color {
+      mode |= 0x01;
+    }
+
+    mode
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn mode() {
+    let cases = [
+      (false, false, false, 0x08),
+      (false, false, true, 0x09),
+      (false, true, false, 0x48),
+      (false, true, true, 0x49),
+      (true, false, false, 0x00),
+      (true, false, true, 0x01),
+      (true, true, false, 0x40),
+      (true, true, true, 0x41),
+    ];
+
+    for (chain, high_resolution, two_color, mode) in cases {
+      assert_eq!(
+        AdvancedMode::mode(chain, high_resolution, two_color),
+        mode,
+        "chain: {chain}, high resolution: {high_resolution}, two colour: {two_color}"
+      );
+    }
+  }
+}
@@ -4,10 +4,26 @@ pub struct Info;
 
 impl Info {
   pub async fn message(printer: &Printer, size_x: u32) -> Result<(), Error> {
+    // Brother's raster command references define the print information
+    // command as ESC i z. ESC i q isn't a command on any of them, so printers
+    // ignored the info it carried.
     let mut message = [
-      0x1b, 0x69, 0x71, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x1b, 0x69, 0x7a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ];
 
+    if printer.flags_contains(PrinterFlags::InfoMediaType) {
+      // Always recover, and check the media type and width against the
+      // loaded tape.
+      message[3] = 0x86;
+      message[4] = printer.status.media_type.into();
+
+      // Die-cut labels also have their length checked.
+      if printer.status.media_length != 0 {
+        message[3] |= 0x08;
+        message[6] = printer.status.media_length;
+      }
+    }
+
     message[5] = printer.status.media_width.into();
 
     for (i, byte) in size_x.to_le_bytes().into_iter().enumerate() {
@@ -0,0 +1,17 @@
+pub struct Initialize;
+
+/// The longest run of invalidate bytes any printer needs, for when the
+/// model isn't known yet.
+#[cfg(any(feature = "network", all(feature = "serial", unix)))]
+pub const MAX_INVALIDATE: u32 = 400;
+
+impl Initialize {
+  /// Clears out anything left over from an interrupted job with `invalidate`
+  /// bytes, then resets the printer.
+  pub fn message(invalidate: u32) -> Vec<u8> {
+    let mut init = vec![0; invalidate as usize];
+    init.extend_from_slice(b"\x1b@");
+
+    init
+  }
+}
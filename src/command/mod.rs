@@ -1,24 +1,32 @@
-use crate::{Error, Printer, PrinterInterface};
+use std::time::{Duration, Instant};
 
+use crate::{Error, Operation, Printer, PrinterInterface, TwoColor};
+
+mod advanced_mode;
 mod d460bt;
 mod finalize;
 mod info;
+mod initialize;
 mod packbits;
 mod precut;
 mod raster_start;
 mod rasterline;
 mod status;
 
+use advanced_mode::AdvancedMode;
 use d460bt::D490bt;
 use finalize::Finalize;
+use futures_timer::Delay;
 use image::DynamicImage;
 use info::Info;
-use nom::Err;
+use initialize::Initialize;
+#[cfg(any(feature = "network", all(feature = "serial", unix)))]
+pub(crate) use initialize::MAX_INVALIDATE;
 use packbits::PackBits;
 use precut::Precut;
 use raster_start::RasterStart;
 use rasterline::RasterLine;
-pub use status::Status;
+pub use status::{Status, StatusField, StatusParseError};
 
 pub struct Commands;
 
@@ -27,24 +35,50 @@ impl Commands {
     printer.send(PackBits::message()).await
   }
 
-  async fn status_internal(printer: &PrinterInterface, attempt: i32) -> Result<Status, Error> {
-    Box::pin(async move {
-      let buf = printer.receive(Status::message()).await?;
-      let status = Status::from_request(buf);
+  pub(crate) async fn initialize(printer: &PrinterInterface, invalidate: u32) -> Result<(), Error> {
+    printer.send(Initialize::message(invalidate)).await
+  }
+
+  pub(crate) async fn status(printer: &PrinterInterface) -> Result<Status, Error> {
+    let policy = printer.status_retry;
+    let started = Instant::now();
+    let mut delay = policy.delay;
+    let mut attempts = 0;
+
+    loop {
+      attempts += 1;
+
+      // Each attempt gets the usual receive timeout, but no more than is
+      // left before the deadline.
+      let left = policy
+        .deadline
+        .map(|deadline| deadline.saturating_sub(started.elapsed()));
+      let timeout = match (printer.timeouts.receive, left) {
+        (Some(timeout), Some(left)) => Some(timeout.min(left)),
+        (timeout, left) => timeout.or(left),
+      };
 
-      match status {
-        Ok(status) => Ok(status),
-        Err(Error::Nom(Err::Error(err))) if err.input.is_empty() && attempt > 0 => {
-          Commands::status_internal(printer, attempt - 1).await
-        },
+      match printer.receive(Status::message(), timeout).await {
+        Ok(buf) if !buf.is_empty() => return Status::from_request(buf),
+        Ok(_)
+        | Err(Error::Timeout {
+          operation: Operation::Receive,
+        }) => {},
         Err(err) => Err(err)?,
       }
-    })
-    .await
-  }
 
-  pub(crate) async fn status(printer: &PrinterInterface) -> Result<Status, Error> {
-    Commands::status_internal(printer, 10).await
+      let remaining = match policy.deadline {
+        Some(deadline) => deadline.saturating_sub(started.elapsed()),
+        None => Duration::MAX,
+      };
+
+      if attempts >= policy.attempts || remaining.is_zero() {
+        Err(Error::NoStatusReply { attempts })?;
+      }
+
+      Delay::new(delay.min(remaining)).await;
+      delay = Duration::try_from_secs_f32(delay.as_secs_f32() * policy.backoff).unwrap_or(delay);
+    }
   }
 
   pub async fn info(printer: &Printer, size_x: u32) -> Result<(), Error> {
@@ -59,6 +93,15 @@ impl Commands {
     D490bt::message(printer, chain).await
   }
 
+  pub async fn advanced_mode(
+    printer: &Printer,
+    chain: bool,
+    high_resolution: bool,
+    two_color: bool,
+  ) -> Result<(), Error> {
+    AdvancedMode::message(printer, chain, high_resolution, two_color).await
+  }
+
   pub async fn precut(printer: &Printer, precut: bool) -> Result<(), Error> {
     Precut::message(printer, precut).await
   }
@@ -67,6 +110,14 @@ impl Commands {
     RasterLine::message(printer, image).await
   }
 
+  pub async fn two_color_raster_line(
+    printer: &Printer,
+    image: DynamicImage,
+    two_color: &TwoColor,
+  ) -> Result<(), Error> {
+    RasterLine::two_color(printer, image, two_color).await
+  }
+
   pub async fn finalize(printer: &Printer, chain: bool) -> Result<(), Error> {
     Finalize::message(printer, chain).await
   }
@@ -1,67 +1,156 @@
 use bitvec::{order::Msb0, view::BitView};
 use image::DynamicImage;
 
-use crate::{Error, Printer};
+use crate::{Error, Printer, PrinterFlags, TapeInfo, TwoColor};
 
 pub struct RasterLine;
 
+/// The command a raster line is sent with.
+#[derive(Clone, Copy)]
+enum Plane {
+  /// `G`, with a little-endian length, on P-touch printers.
+  PTouch,
+  /// `g 00`, with a single length byte, on QL printers.
+  Ql,
+  /// `w`, with the colour and a single length byte, for two-colour printing.
+  Color(u8),
+}
+
+impl Plane {
+  fn header(self, len: usize) -> [u8; 3] {
+    match self {
+      Plane::PTouch => [0x47, len as u8, (len >> 8) as u8],
+      Plane::Ql => [0x67, 0x00, len as u8],
+      Plane::Color(color) => [0x77, color, len as u8],
+    }
+  }
+}
+
 impl RasterLine {
   pub async fn message(printer: &Printer, image: DynamicImage) -> Result<(), Error> {
     let image = image.rotate90().into_luma8();
 
     let image = image::imageops::flip_horizontal(&image);
 
-    let width = image.width();
+    let plane = if printer.flags_contains(PrinterFlags::QlRaster) {
+      Plane::Ql
+    } else {
+      Plane::PTouch
+    };
+
+    let head = Head::new(printer);
+
+    for _ in 0..printer.info.quirks.padding {
+      let line = head.line(std::iter::empty());
 
-    let max_width = printer.ty.info().max_px as usize;
-    let offset = (max_width / 2) - (width as usize / 2);
+      printer
+        .send(RasterLine::row_buffer(printer, line, plane))
+        .await?;
+    }
 
     for row in image.rows() {
-      let mut raster_line = vec![0u8; max_width / 8];
-      let raster_line_view = raster_line.view_bits_mut::<Msb0>();
-
-      for (i, pixel) in row.enumerate() {
-        if pixel.0[0] > 127 {
-          raster_line_view.set(i + offset, false);
-        } else {
-          raster_line_view.set(i + offset, true);
-        }
-      }
+      let line = head.line(row.map(|pixel| pixel.0[0] <= 127));
 
       printer
-        .send(RasterLine::row_buffer(
-          raster_line,
-          printer.flags_contains(crate::PrinterFlags::RasterPackBits),
-        ))
+        .send(RasterLine::row_buffer(printer, line, plane))
         .await?;
     }
 
     Ok(())
   }
 
-  fn row_buffer(row: Vec<u8>, pack_bits: bool) -> Vec<u8> {
-    if pack_bits {
-      let mut buf = vec![0; row.len() + 4];
-      buf[0] = 0x47;
+  /// Sends a black and a red plane for every line of `image`.
+  pub async fn two_color(
+    printer: &Printer,
+    image: DynamicImage,
+    two_color: &TwoColor,
+  ) -> Result<(), Error> {
+    let image = image.rotate90().into_rgb8();
 
-      // Fake compression by encoding a single uncompressed run
-      buf[1] = row.len() as u8 + 1;
-      buf[2] = 0;
-      buf[3] = row.len() as u8 - 1;
+    let image = image::imageops::flip_horizontal(&image);
+
+    let head = Head::new(printer);
+
+    for _ in 0..printer.info.quirks.padding {
+      let line = head.line(std::iter::empty());
+
+      let mut buf = RasterLine::row_buffer(printer, line.clone(), Plane::Color(0x01));
+      buf.extend(RasterLine::row_buffer(printer, line, Plane::Color(0x02)));
+
+      printer.send(buf).await?;
+    }
+
+    for row in image.rows() {
+      let pixels = row.copied().collect::<Vec<_>>();
+
+      let black = head.line(pixels.iter().map(|pixel| two_color.is_black(*pixel)));
+      let red = head.line(pixels.iter().map(|pixel| two_color.is_red(*pixel)));
 
-      buf[4..].copy_from_slice(&row);
+      let mut buf = RasterLine::row_buffer(printer, black, Plane::Color(0x01));
+      buf.extend(RasterLine::row_buffer(printer, red, Plane::Color(0x02)));
+
+      printer.send(buf).await?;
+    }
+
+    Ok(())
+  }
+
+  fn row_buffer(printer: &Printer, row: Vec<u8>, plane: Plane) -> Vec<u8> {
+    if printer.flags_contains(PrinterFlags::RasterPackBits) {
+      let mut buf = plane.header(row.len() + 1).to_vec();
+
+      // Fake compression by encoding a single uncompressed run
+      buf.push(row.len() as u8 - 1);
+      buf.extend_from_slice(&row);
 
       buf
     } else {
-      let mut buf = vec![0; row.len() + 3];
-      buf[0] = 0x47;
+      let mut buf = plane.header(row.len()).to_vec();
+      buf.extend_from_slice(&row);
 
-      buf[1] = row.len() as u8;
-      buf[2] = 0;
+      buf
+    }
+  }
+}
 
-      buf[3..].copy_from_slice(&row);
+/// Where the tape lies under the print head, worked out once per page.
+struct Head {
+  max_px: usize,
+  tape: TapeInfo,
+}
 
-      buf
+impl Head {
+  fn new(printer: &Printer) -> Self {
+    Head {
+      max_px: printer.info.max_px as usize,
+      tape: printer.tape_info(),
     }
   }
+
+  /// Packs one row of the image into a line of the print head, where `dots`
+  /// are the pixels to print.
+  fn line(&self, dots: impl ExactSizeIterator<Item = bool>) -> Vec<u8> {
+    let width = dots.len() as i64;
+
+    // Centre the image on the printable area of the tape, cropping whatever
+    // doesn't fit on it or runs off the head.
+    let tape = self.tape;
+    let printable = tape.offset as usize..((tape.offset + tape.px) as usize).min(self.max_px);
+    let offset = tape.offset as i64 + (tape.px as i64 - width) / 2;
+
+    let mut raster_line = vec![0u8; self.max_px / 8];
+    let raster_line_view = raster_line.view_bits_mut::<Msb0>();
+
+    for (i, dot) in dots.enumerate() {
+      let Ok(x) = usize::try_from(i as i64 + offset) else {
+        continue;
+      };
+
+      if printable.contains(&x) && dot {
+        raster_line_view.set(x, true);
+      }
+    }
+
+    raster_line
+  }
 }
@@ -1,79 +1,277 @@
-use nom::{Finish, IResult, Parser, number::complete::u8};
+use enumflags2::BitFlags;
+use nom::{IResult, Parser, branch::alt, number::complete::u8};
 
 use crate::{
   Error,
+  ErrorFlags,
   MediaType,
+  ModeFlags,
+  Notification,
+  Phase,
+  PrinterFlags,
+  PrinterType,
   TapeColor,
   TapeSize,
   TextColor,
-  nom_utils::{tag, tag_multi, u16, u32, zero, zero_multi},
+  nom_utils::{le_u16, le_u32, tag, zero, zero_multi},
   status_type::StatusType,
 };
 
-fn parse(input: &[u8]) -> IResult<&[u8], Status> {
-  let (input, _) = tag_multi([0x80, 0x20, b'B', b'0'])(input)?;
+/// Walks through a status reply one field at a time, so that a failure can
+/// say exactly which field was wrong and where.
+struct Reader<'a> {
+  raw: &'a [u8],
+  input: &'a [u8],
+}
 
-  let (input, model) = u8(input)?;
+impl<'a> Reader<'a> {
+  fn read<O>(
+    &mut self,
+    field: StatusField,
+    len: usize,
+    expected: &str,
+    mut parser: impl FnMut(&'a [u8]) -> IResult<&'a [u8], O>,
+  ) -> Result<O, StatusParseError> {
+    match parser(self.input) {
+      Ok((input, output)) => {
+        self.input = input;
 
-  let (input, _) = tag(b'0')(input)?;
-  let (input, _) = u16(input)?;
+        Ok(output)
+      },
+      Err(_) => Err(self.error_at(self.offset(), field, len, expected)),
+    }
+  }
 
-  let (input, error) = u16(input)?;
+  fn offset(&self) -> usize {
+    self.raw.len() - self.input.len()
+  }
 
-  let (input, media_width) = TapeSize::parse(input)?;
+  fn error_at(
+    &self,
+    offset: usize,
+    field: StatusField,
+    len: usize,
+    expected: &str,
+  ) -> StatusParseError {
+    let actual = &self.raw[offset.min(self.raw.len())..];
 
-  let (input, media_type) = MediaType::parse(input)?;
+    StatusParseError {
+      offset,
+      field,
+      expected: expected.to_string(),
+      actual: actual[..len.min(actual.len())].to_vec(),
+      raw: self.raw.to_vec(),
+    }
+  }
 
-  let (input, _) = zero_multi::<3>(input)?;
-  let (input, mode) = u8(input)?;
+  fn finish(&self) -> Result<(), StatusParseError> {
+    if self.input.is_empty() {
+      return Ok(());
+    }
 
-  let (input, _) = zero_multi::<2>(input)?;
+    Err(self.error_at(
+      self.offset(),
+      StatusField::End,
+      self.input.len(),
+      "end of reply",
+    ))
+  }
+}
 
-  let (input, status_type) = StatusType::parse(input)?;
-  let (input, phase_type) = u8(input)?;
-  let (input, phase_number) = u16(input)?;
-  let (input, notification_number) = u8(input)?;
+fn parse(input: &[u8]) -> Result<Status, StatusParseError> {
+  let mut reader = Reader { raw: input, input };
 
-  let (input, _) = zero(input)?;
+  reader.read(StatusField::PrintHeadMark, 1, "80", tag(0x80))?;
+  reader.read(StatusField::Size, 1, "20", tag(0x20))?;
+  reader.read(StatusField::BrotherCode, 1, "42", tag(b'B'))?;
+  // P-touch printers report series '0', QL printers '4'.
+  reader.read(StatusField::SeriesCode, 1, "30 or 34", |input| {
+    alt((tag(b'0'), tag(b'4'))).parse(input)
+  })?;
 
-  let (input, tape_color) = TapeColor::parse(input)?;
-  let (input, text_color) = TextColor::parse(input)?;
+  let model = reader.read(StatusField::Model, 1, "a model code", u8)?;
 
-  let (input, hw_setting) = u32(input)?;
+  reader.read(StatusField::Reserved, 1, "30", tag(b'0'))?;
+  reader.read(StatusField::Reserved, 2, "two reserved bytes", le_u16)?;
 
-  nom::combinator::all_consuming(u16).parse(input)?;
+  let error = reader.read(
+    StatusField::ErrorInformation,
+    2,
+    "error flags",
+    ErrorFlags::parse,
+  )?;
 
-  Ok((
-    &[],
-    Status {
-      model,
-      error,
-      media_width,
-      media_type,
-      mode,
-      status_type,
-      phase_type,
-      phase_number,
-      notification_number,
-      tape_color,
-      text_color,
-      hw_setting,
-    },
-  ))
+  let media_width = reader.read(StatusField::MediaWidth, 1, "a media width", u8)?;
+
+  let media_type = reader.read(StatusField::MediaType, 1, "a media type", MediaType::parse)?;
+
+  // What the width means depends on the media type that follows it.
+  let media_width = TapeSize::from_media(media_width, media_type);
+
+  let colors = reader.read(StatusField::NumberOfColors, 1, "a number of colours", u8)?;
+
+  reader.read(StatusField::Reserved, 2, "00 00", zero_multi::<2>)?;
+
+  let mode = reader.read(StatusField::Mode, 1, "mode flags", ModeFlags::parse)?;
+
+  reader.read(StatusField::Reserved, 1, "00", zero)?;
+
+  let media_length = reader.read(StatusField::MediaLength, 1, "a media length", u8)?;
+
+  let status_type = reader.read(
+    StatusField::StatusType,
+    1,
+    "a status type",
+    StatusType::parse,
+  )?;
+  let phase = reader.read(StatusField::Phase, 3, "a phase", Phase::parse)?;
+  let notification = reader.read(
+    StatusField::Notification,
+    1,
+    "a notification",
+    Notification::parse,
+  )?;
+
+  reader.read(StatusField::Reserved, 1, "00", zero)?;
+
+  let tape_color = reader.read(StatusField::TapeColor, 1, "a tape colour", TapeColor::parse)?;
+  let text_color = reader.read(StatusField::TextColor, 1, "a text colour", TextColor::parse)?;
+
+  let hw_setting = reader.read(
+    StatusField::HardwareSettings,
+    4,
+    "hardware settings",
+    le_u32,
+  )?;
+
+  reader.read(StatusField::Reserved, 2, "two reserved bytes", le_u16)?;
+  reader.finish()?;
+
+  Ok(Status {
+    model,
+    error,
+    media_width,
+    media_type,
+    colors,
+    mode,
+    media_length,
+    status_type,
+    phase,
+    notification,
+    tape_color,
+    text_color,
+    hw_setting,
+  })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusField {
+  PrintHeadMark,
+  Size,
+  BrotherCode,
+  SeriesCode,
+  Model,
+  Reserved,
+  ErrorInformation,
+  MediaWidth,
+  MediaType,
+  NumberOfColors,
+  Mode,
+  MediaLength,
+  StatusType,
+  Phase,
+  Notification,
+  TapeColor,
+  TextColor,
+  HardwareSettings,
+  End,
+}
+
+impl std::fmt::Display for StatusField {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      StatusField::PrintHeadMark => "print head mark",
+      StatusField::Size => "size",
+      StatusField::BrotherCode => "Brother code",
+      StatusField::SeriesCode => "series code",
+      StatusField::Model => "model code",
+      StatusField::Reserved => "reserved",
+      StatusField::ErrorInformation => "error information",
+      StatusField::MediaWidth => "media width",
+      StatusField::MediaType => "media type",
+      StatusField::NumberOfColors => "number of colours",
+      StatusField::Mode => "mode",
+      StatusField::MediaLength => "media length",
+      StatusField::StatusType => "status type",
+      StatusField::Phase => "phase",
+      StatusField::Notification => "notification",
+      StatusField::TapeColor => "tape colour",
+      StatusField::TextColor => "text colour",
+      StatusField::HardwareSettings => "hardware settings",
+      StatusField::End => "end of reply",
+    };
+
+    f.write_str(name)
+  }
+}
+
+/// A status reply that didn't match the documented 32-byte layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusParseError {
+  /// Offset of the offending field from the start of the reply.
+  pub offset: usize,
+  pub field: StatusField,
+  pub expected: String,
+  /// The bytes found where the field should have been, which is empty if
+  /// the reply was cut short.
+  pub actual: Vec<u8>,
+  /// The whole reply, for bug reports.
+  pub raw: Vec<u8>,
+}
+
+impl std::fmt::Display for StatusParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Malformed status reply: {} at byte {}: expected {}, ",
+      self.field, self.offset, self.expected
+    )?;
+
+    if self.actual.is_empty() {
+      write!(f, "but the reply is only {} bytes long", self.raw.len())?;
+    } else {
+      write!(f, "got {}", hex(&self.actual))?;
+    }
+
+    write!(f, " (reply: {})", hex(&self.raw))
+  }
+}
+
+impl std::error::Error for StatusParseError {}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect::<Vec<_>>()
+    .join(" ")
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Status {
   pub model: u8,
-  pub error: u16,
+  pub error: BitFlags<ErrorFlags>,
   pub media_width: TapeSize,
   pub media_type: MediaType,
-  pub mode: u8,
+  /// Number of ink colours the media supports, on printers that report it.
+  pub colors: u8,
+  pub mode: BitFlags<ModeFlags>,
+  /// Length of die-cut labels in mm, or 0 for continuous tape.
+  pub media_length: u8,
   pub status_type: StatusType,
-  pub phase_type: u8,
-  pub phase_number: u16,
-  pub notification_number: u8,
+  pub phase: Phase,
+  pub notification: Notification,
   pub tape_color: TapeColor,
   pub text_color: TextColor,
   pub hw_setting: u32,
@@ -85,12 +283,57 @@ impl Status {
   }
 
   pub fn from_request(buf: Vec<u8>) -> Result<Self, Error> {
-    match parse(&buf).finish() {
-      Ok((_, status)) => Ok(status),
-      Err(nom::error::Error { code, input }) => Err(nom::Err::Error(nom::error::Error {
-        code,
-        input: input.to_vec(),
-      }))?,
+    Ok(parse(&buf)?)
+  }
+
+  /// The printer model identified by the status reply itself, as opposed
+  /// to its USB IDs.
+  pub fn printer_type(&self) -> Option<PrinterType> {
+    PrinterType::from_model_code(self.model)
+  }
+
+  /// The status an idle `ty` would report with `tape` loaded, for printing
+  /// without a printer to ask.
+  pub fn assumed(ty: PrinterType, tape: TapeSize) -> Self {
+    let media_type = match tape {
+      TapeSize::None => MediaType::None,
+      TapeSize::HeatShrink5_8
+      | TapeSize::HeatShrink8_8
+      | TapeSize::HeatShrink11_7
+      | TapeSize::HeatShrink17_7
+      | TapeSize::HeatShrink23_6 => MediaType::HeatShrink,
+      TapeSize::HeatShrink5_2
+      | TapeSize::HeatShrink9_0
+      | TapeSize::HeatShrink11_2
+      | TapeSize::HeatShrink21_0
+      | TapeSize::HeatShrink31_0 => MediaType::HeatShrink3To1,
+      TapeSize::Fle21 => MediaType::Fle,
+      TapeSize::Continuous(_) => MediaType::Continuous,
+      TapeSize::DieCut(_) => MediaType::DieCut,
+      _ => MediaType::Laminated,
+    };
+
+    // 62 mm continuous tape is the only DK media made in black and red, so
+    // it's assumed to be that on printers that can print both.
+    let colors = match tape {
+      TapeSize::Continuous(62) if ty.info().flags.contains(PrinterFlags::TwoColor) => 2,
+      _ => 0,
+    };
+
+    Status {
+      model: ty.info().model_code.unwrap_or(0),
+      error: BitFlags::empty(),
+      media_width: tape,
+      media_type,
+      colors,
+      mode: BitFlags::empty(),
+      media_length: 0,
+      status_type: StatusType::Ok,
+      phase: Phase::Editing,
+      notification: Notification::None,
+      tape_color: TapeColor::White,
+      text_color: TextColor::Black,
+      hw_setting: 0,
     }
   }
 }
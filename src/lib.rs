@@ -1,20 +1,79 @@
+//! Print images on Brother P-Touch label makers and QL label printers.
+//!
+//! # Async runtimes
+//!
+//! The async API doesn't rely on any particular executor: USB transfers are
+//! completed by nusb's own event thread, and timeouts are driven by a timer
+//! thread, so [`Printer`] works the same under tokio, smol, async-std or a
+//! bare `block_on`.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), ptouch_rs::Error> {
+//! smol::block_on(async {
+//!   let printer = ptouch_rs::Printer::open().await?;
+//!   let image = image::open("label.png").expect("label.png should be an image");
+//!
+//!   printer.print(image).await
+//! })
+//! # }
+//! ```
+//!
+//! For programs without any async code at all, the `blocking` feature adds a
+//! synchronous `blocking::Printer`.
+//!
+//! The `network` feature adds `Printer::connect`, for printers that take raw
+//! print jobs over TCP, and the `serial` feature `Printer::open_serial`, for
+//! printers paired over Bluetooth on Unix. Both are driven by async-io's own
+//! reactor thread, so they're just as executor-agnostic.
+
 mod tape_stats;
+use std::{
+  future::Future,
+  io::Write,
+  sync::{
+    Mutex,
+    atomic::{AtomicBool, Ordering},
+  },
+  time::Duration,
+};
+
 use nusb::{Interface, transfer::RequestBuffer};
 pub use tape_stats::{TapeInfo, TapeSize};
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod command;
 mod media_type;
 pub(crate) mod nom_utils;
+mod notification;
+mod open_options;
+mod phase;
+mod preview;
+mod print_options;
 mod printer_stats;
+mod registry;
+#[cfg(feature = "serde")]
+pub mod report;
+mod status_flags;
 mod status_type;
 mod tape_color;
+mod tape_usage;
 mod text_color;
+mod timeout;
 
-pub use command::{Commands, Status};
+pub use command::{Commands, Status, StatusField, StatusParseError};
 pub use media_type::MediaType;
-pub use printer_stats::{PrinterFlags, PrinterInfo, PrinterType};
+pub use notification::Notification;
+pub use open_options::{OpenOptions, RetryPolicy, Timeouts};
+pub use phase::Phase;
+pub use preview::Preview;
+pub use print_options::{Align, PrintOptions, TwoColor};
+pub use printer_stats::{PrinterFlags, PrinterInfo, PrinterType, Quirks};
+pub use registry::{CustomIndex, DefinitionError, PrinterDefinition, TapeGeometry};
+pub use status_flags::{ErrorFlags, ModeFlags};
 pub use status_type::StatusType;
 pub use tape_color::TapeColor;
+pub use tape_usage::{TapeCounter, TapeUsage};
 pub use text_color::TextColor;
 
 #[derive(Debug, thiserror::Error)]
@@ -24,15 +83,65 @@ pub enum Error {
   #[error(transparent)]
   NusbTransfer(#[from] nusb::transfer::TransferError),
   #[error(transparent)]
-  Nom(#[from] nom::Err<nom::error::Error<Vec<u8>>>),
+  Io(std::io::Error),
+  #[error(transparent)]
+  StatusParse(#[from] StatusParseError),
   #[error("Printer not found")]
   PrinterNotFound,
-  #[error("Invalid tape size reported: {0}")]
+  #[error("Can't print on tape of unsupported width {0} mm")]
   InvalidTapeSize(u8),
   #[error("Printer status: {0:?}")]
   Status(StatusType),
   #[error("No tape loaded")]
   NoTapeLoaded,
+  #[error("Printer didn't answer a status request after {attempts} attempts")]
+  NoStatusReply { attempts: u32 },
+  #[error("Expected {expected:?} but the printer reports model code {model_code:#04x}")]
+  ModelMismatch {
+    expected: PrinterType,
+    model_code: u8,
+  },
+  #[error("Label length of {length} mm is below the printer's minimum of {minimum} mm")]
+  LabelTooShort { length: f32, minimum: f32 },
+  #[error("Label length of {length} mm is above the printer's maximum of {maximum} mm")]
+  LabelTooLongForPrinter { length: f32, maximum: f32 },
+  #[error("Image is {image} mm long, which doesn't fit on a {length} mm label")]
+  LabelTooLong { length: f32, image: f32 },
+  #[error("{printer:?} doesn't support {feature}")]
+  Unsupported {
+    printer: PrinterType,
+    feature: &'static str,
+  },
+  #[error("Printer reports unknown model code {model_code:#04x}")]
+  UnknownModel { model_code: u8 },
+  #[error("Timed out during {operation}")]
+  Timeout { operation: Operation },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+  Connect,
+  Send,
+  Receive,
+}
+
+impl std::fmt::Display for Operation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Operation::Connect => write!(f, "connect"),
+      Operation::Send => write!(f, "send"),
+      Operation::Receive => write!(f, "receive"),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectedPrinter {
+  pub ty: PrinterType,
+  pub bus_number: u8,
+  pub device_address: u8,
+  pub serial_number: Option<String>,
 }
 
 #[derive(Debug)]
@@ -40,39 +149,241 @@ pub struct Printer {
   interface: PrinterInterface,
   status: Status,
   ty: PrinterType,
+  serial_number: Option<String>,
+  /// Looked up once when the printer is opened, with any quirks it was
+  /// opened with in place of the known ones.
+  info: PrinterInfo,
+  /// Set while a page is being sent, so that if the future sending it is
+  /// dropped part-way through, the next job knows to reset the printer.
+  interrupted: AtomicBool,
 }
 
 struct PrinterInterface {
-  interface: Interface,
+  transport: Transport,
+  timeouts: Timeouts,
+  status_retry: RetryPolicy,
+}
+
+enum Transport {
+  Usb(Interface),
+  #[cfg(feature = "network")]
+  Tcp(async_io::Async<std::net::TcpStream>),
+  #[cfg(all(feature = "serial", unix))]
+  Serial(async_io::Async<std::fs::File>),
+  /// Writes the command stream out without a printer to talk to.
+  Sink(Mutex<Box<dyn Write + Send>>),
 }
 
 impl std::fmt::Debug for PrinterInterface {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let transport = match &self.transport {
+      Transport::Usb(_) => "<usb interface>".to_string(),
+      #[cfg(feature = "network")]
+      Transport::Tcp(stream) => match stream.get_ref().peer_addr() {
+        Ok(addr) => format!("<tcp {addr}>"),
+        Err(_) => "<tcp>".to_string(),
+      },
+      #[cfg(all(feature = "serial", unix))]
+      Transport::Serial(_) => "<serial port>".to_string(),
+      Transport::Sink(_) => "<writer>".to_string(),
+    };
+
     f.debug_struct("PrinterInterface")
-      .field("interface", &"<usb interface>")
+      .field("transport", &transport)
+      .field("timeouts", &self.timeouts)
+      .field("status_retry", &self.status_retry)
       .finish()
   }
 }
 
+/// Every status reply is 32 bytes long.
+const STATUS_LEN: usize = 32;
+
+/// The print head mark and size every status reply starts with.
+#[cfg(any(feature = "network", all(feature = "serial", unix)))]
+const STATUS_HEADER: [u8; 2] = [0x80, 0x20];
+
 impl PrinterInterface {
   async fn send(&self, data: impl Into<Vec<u8>>) -> Result<(), Error> {
-    self.interface.bulk_out(0x02, data.into()).await.status?;
+    match &self.transport {
+      Transport::Usb(interface) => {
+        let transfer = interface.bulk_out(0x02, data.into());
+
+        Self::with_timeout(self.timeouts.send, Operation::Send, transfer)
+          .await?
+          .status?;
+      },
+      #[cfg(feature = "network")]
+      Transport::Tcp(stream) => self.write_stream(stream, data.into()).await?,
+      #[cfg(all(feature = "serial", unix))]
+      Transport::Serial(port) => self.write_stream(port, data.into()).await?,
+      Transport::Sink(writer) => {
+        let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+
+        writer.write_all(&data.into()).map_err(Error::Io)?;
+      },
+    }
+
+    Ok(())
+  }
+
+  /// Pushes out whatever a writer is holding on to. Every other transport
+  /// has sent each command by the time [`PrinterInterface::send`] returns.
+  fn flush(&self) -> Result<(), Error> {
+    if let Transport::Sink(writer) = &self.transport {
+      let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+
+      writer.flush().map_err(Error::Io)?;
+    }
 
     Ok(())
   }
 
-  async fn receive(&self, data: impl Into<Vec<u8>>) -> Result<Vec<u8>, Error> {
+  /// Sends `data` and reads the reply, waiting up to `timeout` for it.
+  async fn receive(
+    &self,
+    data: impl Into<Vec<u8>>,
+    timeout: Option<Duration>,
+  ) -> Result<Vec<u8>, Error> {
+    match &self.transport {
+      #[cfg(feature = "network")]
+      Transport::Tcp(stream) => Self::discard_stream(stream).await?,
+      #[cfg(all(feature = "serial", unix))]
+      Transport::Serial(port) => Self::discard_stream(port).await?,
+      _ => {},
+    }
+
     self.send(data.into()).await?;
 
-    let buf = RequestBuffer::new(32);
-    let res = self.interface.bulk_in(0x81, buf).await.into_result()?;
+    match &self.transport {
+      Transport::Usb(interface) => {
+        let buf = RequestBuffer::new(STATUS_LEN);
+        let transfer = interface.bulk_in(0x81, buf);
+
+        let res = Self::with_timeout(timeout, Operation::Receive, transfer)
+          .await?
+          .into_result()?;
+
+        Ok(res)
+      },
+      #[cfg(feature = "network")]
+      Transport::Tcp(stream) => Self::read_stream(stream, timeout).await,
+      #[cfg(all(feature = "serial", unix))]
+      Transport::Serial(port) => Self::read_stream(port, timeout).await,
+      Transport::Sink(_) => Err(Error::Io(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "nothing to read a reply from",
+      ))),
+    }
+  }
+
+  #[cfg(any(feature = "network", all(feature = "serial", unix)))]
+  async fn write_stream(
+    &self,
+    mut stream: impl futures_lite::AsyncWrite + Unpin,
+    data: Vec<u8>,
+  ) -> Result<(), Error> {
+    use futures_lite::AsyncWriteExt;
+
+    let write = async { stream.write_all(&data).await };
+
+    Self::with_timeout(self.timeouts.send, Operation::Send, write)
+      .await?
+      .map_err(Error::Io)
+  }
+
+  #[cfg(any(feature = "network", all(feature = "serial", unix)))]
+  async fn read_stream(
+    mut stream: impl futures_lite::AsyncRead + Unpin,
+    timeout: Option<Duration>,
+  ) -> Result<Vec<u8>, Error> {
+    use futures_lite::AsyncReadExt;
+
+    // A stream has no transfer boundaries, so read until there's a whole
+    // reply, skipping anything ahead of the header it starts with.
+    let read = async {
+      let mut buf = Vec::with_capacity(STATUS_LEN);
+      let mut chunk = [0; STATUS_LEN];
+
+      loop {
+        let start = (0..buf.len())
+          .find(|&i| STATUS_HEADER.starts_with(&buf[i..buf.len().min(i + STATUS_HEADER.len())]))
+          .unwrap_or(buf.len());
+        buf.drain(..start);
+
+        if buf.len() >= STATUS_LEN {
+          buf.truncate(STATUS_LEN);
+
+          return Ok(buf);
+        }
+
+        match stream.read(&mut chunk).await? {
+          0 => Err(std::io::ErrorKind::UnexpectedEof)?,
+          n => buf.extend_from_slice(&chunk[..n]),
+        }
+      }
+    };
+
+    Self::with_timeout(timeout, Operation::Receive, read)
+      .await?
+      .map_err(Error::Io)
+  }
+
+  /// Throws away anything already waiting to be read, such as the rest of
+  /// a reply to a request that timed out, so it isn't taken for the answer
+  /// to the next one.
+  #[cfg(any(feature = "network", all(feature = "serial", unix)))]
+  async fn discard_stream(mut stream: impl futures_lite::AsyncRead + Unpin) -> Result<(), Error> {
+    use futures_lite::{AsyncReadExt, future};
+
+    let mut buf = [0; STATUS_LEN];
+
+    loop {
+      match future::poll_once(stream.read(&mut buf)).await {
+        Some(Ok(0)) | None => return Ok(()),
+        Some(Ok(_)) => {},
+        Some(Err(err)) => return Err(Error::Io(err)),
+      }
+    }
+  }
 
-    Ok(res)
+  async fn with_timeout<T>(
+    duration: Option<Duration>,
+    operation: Operation,
+    future: impl Future<Output = T>,
+  ) -> Result<T, Error> {
+    match duration {
+      Some(duration) => timeout::timeout(duration, future)
+        .await
+        .ok_or(Error::Timeout { operation }),
+      None => Ok(future.await),
+    }
   }
 }
 
 impl Printer {
+  pub fn list() -> Result<Vec<ConnectedPrinter>, Error> {
+    let printers = nusb::list_devices()?
+      .filter_map(|device| {
+        let ty = PrinterType::from_usb(device.vendor_id(), device.product_id())?;
+
+        Some(ConnectedPrinter {
+          ty,
+          bus_number: device.bus_number(),
+          device_address: device.device_address(),
+          serial_number: device.serial_number().map(String::from),
+        })
+      })
+      .collect();
+
+    Ok(printers)
+  }
+
   pub async fn open() -> Result<Self, Error> {
+    Printer::open_with(OpenOptions::default()).await
+  }
+
+  pub async fn open_with(options: OpenOptions) -> Result<Self, Error> {
     let found = nusb::list_devices()?.find_map(|device| {
       let printer = PrinterType::from_usb(device.vendor_id(), device.product_id())?;
 
@@ -84,26 +395,259 @@ impl Printer {
       None => Err(Error::PrinterNotFound)?,
     };
 
+    let serial_number = device.serial_number().map(String::from);
     let device = device.open()?;
     let interface = device.detach_and_claim_interface(0)?;
 
-    let interface = PrinterInterface { interface };
+    let interface = PrinterInterface {
+      transport: Transport::Usb(interface),
+      timeouts: options.timeouts,
+      status_retry: options.status_retry,
+    };
 
-    let mut init = vec![0; 102];
-    init[100] = 0x1b;
-    init[101] = 0x40;
-    interface.send(init).await?;
+    Commands::initialize(&interface, ty.info().invalidate).await?;
 
     let status = Commands::status(&interface).await?;
 
+    Printer::check_model(ty, &status, options.strict_model_check)?;
+
     Ok(Printer {
       interface,
       ty,
+      serial_number,
+      info: Printer::info_with(ty, options.quirks),
       status,
+      interrupted: AtomicBool::new(false),
     })
   }
 
+  /// Connects to a printer listening for raw print jobs over TCP, usually on
+  /// port 9100.
+  ///
+  /// Status is read from the printer's replies on the same connection, as
+  /// over USB. Brother's SNMP status MIB isn't supported.
+  #[cfg(feature = "network")]
+  pub async fn connect(addr: impl std::net::ToSocketAddrs) -> Result<Self, Error> {
+    Printer::connect_with(addr, OpenOptions::default()).await
+  }
+
+  /// There's no USB descriptor to go by over the network, so the printer is
+  /// identified by the model code in its status reply, or checked against
+  /// [`OpenOptions::model`] if that's given.
+  #[cfg(feature = "network")]
+  pub async fn connect_with(
+    addr: impl std::net::ToSocketAddrs,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    let mut last_error = None;
+    let mut stream = None;
+
+    for addr in addr.to_socket_addrs().map_err(Error::Io)? {
+      let connect = async_io::Async::<std::net::TcpStream>::connect(addr);
+
+      match PrinterInterface::with_timeout(options.timeouts.connect, Operation::Connect, connect)
+        .await?
+      {
+        Ok(connected) => {
+          stream = Some(connected);
+          break;
+        },
+        Err(e) => last_error = Some(e),
+      }
+    }
+
+    let Some(stream) = stream else {
+      Err(Error::Io(last_error.unwrap_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "no address to connect to")
+      })))?
+    };
+
+    Printer::identify(Transport::Tcp(stream), options).await
+  }
+
+  /// Opens a printer on a serial port, such as the `/dev/rfcomm*` device of
+  /// a printer paired over Bluetooth.
+  #[cfg(all(feature = "serial", unix))]
+  pub async fn open_serial(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+    Printer::open_serial_with(path, OpenOptions::default()).await
+  }
+
+  /// Like [`Printer::connect_with`], the printer is identified by the model
+  /// code in its status reply.
+  ///
+  /// The port is switched to raw mode but its baud rate is left as it is,
+  /// and anything already buffered on it isn't flushed. Neither matters for
+  /// an rfcomm device, which ignores the baud rate and starts empty, but a
+  /// wired serial port has to be set up beforehand.
+  #[cfg(all(feature = "serial", unix))]
+  pub async fn open_serial_with(
+    path: impl AsRef<std::path::Path>,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    use rustix::termios::{OptionalActions, tcgetattr, tcsetattr};
+
+    let port = std::fs::OpenOptions::new()
+      .read(true)
+      .write(true)
+      .open(path)
+      .map_err(Error::Io)?;
+
+    // Raster data is binary, so the line discipline mustn't translate or
+    // echo any of it.
+    let mut termios = tcgetattr(&port).map_err(|e| Error::Io(e.into()))?;
+    termios.make_raw();
+    tcsetattr(&port, OptionalActions::Now, &termios).map_err(|e| Error::Io(e.into()))?;
+
+    let port = async_io::Async::new(port).map_err(Error::Io)?;
+
+    Printer::identify(Transport::Serial(port), options).await
+  }
+
+  /// Sets up a printer over a transport with no descriptor to identify it
+  /// by, going by its status reply or the model it's expected to be.
+  #[cfg(any(feature = "network", all(feature = "serial", unix)))]
+  async fn identify(transport: Transport, options: OpenOptions) -> Result<Self, Error> {
+    let interface = PrinterInterface {
+      transport,
+      timeouts: options.timeouts,
+      status_retry: options.status_retry,
+    };
+
+    // The model isn't known until it answers, so it gets as long a run of
+    // invalidate bytes as any printer needs.
+    Commands::initialize(&interface, command::MAX_INVALIDATE).await?;
+
+    let status = Commands::status(&interface).await?;
+
+    let ty = match options.model {
+      Some(ty) => {
+        Printer::check_model(ty, &status, options.strict_model_check)?;
+
+        ty
+      },
+      None => match status.printer_type() {
+        Some(ty) => ty,
+        None => Err(Error::UnknownModel {
+          model_code: status.model,
+        })?,
+      },
+    };
+
+    Ok(Printer {
+      interface,
+      ty,
+      serial_number: None,
+      info: Printer::info_with(ty, options.quirks),
+      status,
+      interrupted: AtomicBool::new(false),
+    })
+  }
+
+  /// Writes everything that would be sent to a `ty` printer with `tape`
+  /// loaded into `writer`, starting with the same reset a real printer gets
+  /// when it's opened.
+  pub async fn write_to(
+    writer: impl Write + Send + 'static,
+    ty: PrinterType,
+    tape: TapeSize,
+  ) -> Result<Self, Error> {
+    Printer::write_to_with(writer, ty, tape, OpenOptions::default()).await
+  }
+
+  /// Only the quirks in `options` apply, since there's no printer to time
+  /// out or check the model of.
+  pub async fn write_to_with(
+    writer: impl Write + Send + 'static,
+    ty: PrinterType,
+    tape: TapeSize,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    let interface = PrinterInterface {
+      transport: Transport::Sink(Mutex::new(Box::new(writer))),
+      timeouts: options.timeouts,
+      status_retry: options.status_retry,
+    };
+
+    Commands::initialize(&interface, ty.info().invalidate).await?;
+    interface.flush()?;
+
+    Ok(Printer {
+      interface,
+      ty,
+      serial_number: None,
+      info: Printer::info_with(ty, options.quirks),
+      status: Status::assumed(ty, tape),
+      interrupted: AtomicBool::new(false),
+    })
+  }
+
+  /// Like [`Printer::write_to`], into a newly created file at `path`. Writes
+  /// to the file are buffered, and flushed at the end of each page.
+  pub async fn create(
+    path: impl AsRef<std::path::Path>,
+    ty: PrinterType,
+    tape: TapeSize,
+  ) -> Result<Self, Error> {
+    Printer::create_with(path, ty, tape, OpenOptions::default()).await
+  }
+
+  pub async fn create_with(
+    path: impl AsRef<std::path::Path>,
+    ty: PrinterType,
+    tape: TapeSize,
+    options: OpenOptions,
+  ) -> Result<Self, Error> {
+    let file = std::fs::File::create(path).map_err(Error::Io)?;
+
+    Printer::write_to_with(std::io::BufWriter::new(file), ty, tape, options).await
+  }
+
+  fn info_with(ty: PrinterType, quirks: Option<Quirks>) -> PrinterInfo {
+    let mut info = ty.info();
+
+    if let Some(quirks) = quirks {
+      info.quirks = quirks;
+    }
+
+    info
+  }
+
+  /// Brother reuses product IDs across firmware variants, so the model
+  /// code in the status reply is the more reliable of the two.
+  fn check_model(ty: PrinterType, status: &Status, strict: bool) -> Result<(), Error> {
+    let Some(model_code) = ty.info().model_code else {
+      return Ok(());
+    };
+
+    if model_code == status.model {
+      return Ok(());
+    }
+
+    if strict {
+      Err(Error::ModelMismatch {
+        expected: ty,
+        model_code: status.model,
+      })?;
+    }
+
+    log::warn!(
+      "{ty:?} reports model code {:#04x} ({:?}), expected {model_code:#04x}",
+      status.model,
+      status.printer_type(),
+    );
+
+    Ok(())
+  }
+
+  /// Without a printer to ask, as after [`Printer::write_to`], this is the
+  /// assumed status.
   pub async fn reload_status(&mut self) -> Result<Status, Error> {
+    if let Transport::Sink(_) = self.interface.transport {
+      return Ok(self.status.clone());
+    }
+
+    self.recover().await?;
+
     let status = Commands::status(&self.interface).await?;
     self.status = status.clone();
 
@@ -118,8 +662,34 @@ impl Printer {
     self.status.clone()
   }
 
+  /// Workarounds applied to everything sent to this printer.
+  pub fn quirks(&self) -> Quirks {
+    self.info.quirks
+  }
+
+  /// The USB serial number of the printer, if it reports one.
+  pub fn serial_number(&self) -> Option<&str> {
+    self.serial_number.as_deref()
+  }
+
+  /// Geometry of the loaded tape on this printer.
+  pub fn tape_info(&self) -> TapeInfo {
+    let mut tape = self.ty.tape_info(self.status.media_width);
+
+    // Dots the printer needs left blank push the printable area along the
+    // head, rather than cropping it.
+    let leading_px = self.info.quirks.leading_px;
+
+    if tape.offset < leading_px {
+      tape.offset = leading_px.min(self.info.max_px);
+      tape.px = tape.px.min(self.info.max_px - tape.offset);
+    }
+
+    tape
+  }
+
   fn flags_contains(&self, flag: PrinterFlags) -> bool {
-    self.ty.info().flags.contains(flag)
+    self.info.flags.contains(flag)
   }
 
   async fn send(&self, data: impl Into<Vec<u8>>) -> Result<(), Error> {
@@ -127,6 +697,157 @@ impl Printer {
   }
 
   pub async fn print(&self, image: image::DynamicImage) -> Result<(), Error> {
+    self.print_with(image, &PrintOptions::default()).await
+  }
+
+  pub async fn print_with(
+    &self,
+    image: image::DynamicImage,
+    options: &PrintOptions,
+  ) -> Result<(), Error> {
+    self.check_ready()?;
+
+    if options.high_resolution && !self.flags_contains(PrinterFlags::HighResolution) {
+      Err(Error::Unsupported {
+        printer: self.ty,
+        feature: "high resolution printing",
+      })?;
+    }
+
+    if options.two_color.is_some() && !self.flags_contains(PrinterFlags::TwoColor) {
+      Err(Error::Unsupported {
+        printer: self.ty,
+        feature: "two-colour printing",
+      })?;
+    }
+
+    if options.two_color.is_some() && self.status.colors < 2 {
+      Err(Error::Unsupported {
+        printer: self.ty,
+        feature: "two-colour printing on the loaded media",
+      })?;
+    }
+
+    let image = self.layout(image, options)?;
+
+    if let Some(maximum) = self.info.quirks.max_length {
+      let length = self.info.mm(image.width() + self.info.quirks.padding);
+
+      if length > maximum {
+        Err(Error::LabelTooLongForPrinter { length, maximum })?;
+      }
+    }
+
+    // Dithering to a single level would lose the red, so it only applies to
+    // black and white prints.
+    let image = if options.dither && options.two_color.is_none() {
+      let mut image = image.into_luma8();
+      image::imageops::dither(&mut image, &image::imageops::BiLevel);
+
+      image::DynamicImage::ImageLuma8(image)
+    } else {
+      image
+    };
+
+    // The source image is at the printer's normal resolution, so each line
+    // is printed twice to keep the label the same length.
+    let image = if options.high_resolution {
+      image.resize_exact(
+        image.width() * 2,
+        image.height(),
+        image::imageops::FilterType::Nearest,
+      )
+    } else {
+      image
+    };
+
+    for copy in 1..=options.copies {
+      let chain = options.chain || copy < options.copies;
+
+      self.print_page(image.clone(), chain, options).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Physical length in millimetres of each label `print_with` would
+  /// produce for `image`.
+  pub fn label_length(
+    &self,
+    image: &image::DynamicImage,
+    options: &PrintOptions,
+  ) -> Result<f32, Error> {
+    let lines = self.ty.label_lines(image.width(), options)?;
+
+    Ok(self.info.mm(lines + self.info.quirks.padding))
+  }
+
+  /// Estimates the tape used by printing every image in `images` with
+  /// `options`, taking this printer's quirks into account.
+  pub fn tape_usage(
+    &self,
+    images: &[image::DynamicImage],
+    options: &PrintOptions,
+  ) -> Result<TapeUsage, Error> {
+    TapeUsage::estimate_with(self.ty, self.info.quirks, images, options)
+  }
+
+  /// Pads `image` along the feed direction to the length of the label.
+  fn layout(
+    &self,
+    image: image::DynamicImage,
+    options: &PrintOptions,
+  ) -> Result<image::DynamicImage, Error> {
+    let lines = self.ty.label_lines(image.width(), options)?;
+
+    if lines == image.width() {
+      return Ok(image);
+    }
+
+    let x = match options.align {
+      Align::Start => 0,
+      Align::Centre => (lines - image.width()) / 2,
+      Align::End => lines - image.width(),
+    };
+
+    let mut label = image::RgbaImage::from_pixel(lines, image.height(), image::Rgba([0xff; 4]));
+    image::imageops::overlay(&mut label, &image.into_rgba8(), x as i64, 0);
+
+    Ok(image::DynamicImage::ImageRgba8(label))
+  }
+
+  /// Feeds `lines` blank raster lines through the printer.
+  pub async fn feed(&self, lines: u32) -> Result<(), Error> {
+    self.check_ready()?;
+
+    let options = PrintOptions {
+      precut: false,
+      ..Default::default()
+    };
+
+    self
+      .print_page(Printer::blank(lines), false, &options)
+      .await
+  }
+
+  /// Feeds the shortest possible blank label, cutting it off with the
+  /// cutter.
+  pub async fn cut(&self) -> Result<(), Error> {
+    self.check_ready()?;
+
+    let options = PrintOptions {
+      precut: true,
+      ..Default::default()
+    };
+
+    self.print_page(Printer::blank(1), false, &options).await
+  }
+
+  fn blank(lines: u32) -> image::DynamicImage {
+    image::DynamicImage::ImageLuma8(image::GrayImage::from_pixel(lines, 1, image::Luma([0xff])))
+  }
+
+  fn check_ready(&self) -> Result<(), Error> {
     if self.status.media_type == MediaType::None {
       Err(Error::NoTapeLoaded)?;
     }
@@ -135,6 +856,32 @@ impl Printer {
       Err(Error::Status(self.status.status_type))?;
     }
 
+    if let TapeSize::Unknown(width) = self.status.media_width {
+      Err(Error::InvalidTapeSize(width))?;
+    }
+
+    Ok(())
+  }
+
+  /// Resets the printer if the last page never finished sending.
+  async fn recover(&self) -> Result<(), Error> {
+    if self.interrupted.load(Ordering::SeqCst) {
+      Commands::initialize(&self.interface, self.info.invalidate).await?;
+      self.interrupted.store(false, Ordering::SeqCst);
+    }
+
+    Ok(())
+  }
+
+  async fn print_page(
+    &self,
+    image: image::DynamicImage,
+    chain: bool,
+    options: &PrintOptions,
+  ) -> Result<(), Error> {
+    self.recover().await?;
+    self.interrupted.store(true, Ordering::SeqCst);
+
     if self.flags_contains(PrinterFlags::RasterPackBits) {
       Commands::pack_bits(self).await?;
     }
@@ -142,21 +889,98 @@ impl Printer {
     Commands::raster_start(self).await?;
 
     if self.flags_contains(PrinterFlags::UseInfoCmd) {
-      Commands::info(self, image.width()).await?;
+      Commands::info(self, image.width() + self.info.quirks.padding).await?;
     }
 
     if self.flags_contains(PrinterFlags::D460BTMagic) {
-      Commands::d460bt_magic(self, false).await?;
+      Commands::d460bt_magic(self, chain).await?;
     }
 
     if self.flags_contains(PrinterFlags::HasPrecut) {
-      Commands::precut(self, true).await?;
+      Commands::precut(self, options.precut).await?;
     }
 
-    Commands::raster_line(self, image).await?;
+    // Printers that take advanced mode only feed and cut after the last
+    // label when it says to, so it's sent for every page, chained or not.
+    if self.flags_contains(PrinterFlags::HighResolution)
+      || self.flags_contains(PrinterFlags::TwoColor)
+    {
+      Commands::advanced_mode(
+        self,
+        chain,
+        options.high_resolution,
+        options.two_color.is_some(),
+      )
+      .await?;
+    }
+
+    match &options.two_color {
+      Some(two_color) => Commands::two_color_raster_line(self, image, two_color).await?,
+      None => Commands::raster_line(self, image).await?,
+    }
 
-    Commands::finalize(self, false).await?;
+    Commands::finalize(self, chain).await?;
+    self.interface.flush()?;
+
+    self.interrupted.store(false, Ordering::SeqCst);
 
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn printer() -> Printer {
+    futures_lite::future::block_on(Printer::write_to(
+      std::io::sink(),
+      PrinterType::PT_P700,
+      TapeSize::Twelve,
+    ))
+    .unwrap()
+  }
+
+  /// Lays out a black image `lines` long, returning how long the label is
+  /// and which of its lines the image starts on.
+  fn layout(lines: u32, length: Option<f32>, align: Align) -> Result<(u32, u32), Error> {
+    let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(lines, 76));
+    let options = PrintOptions {
+      length,
+      align,
+      ..Default::default()
+    };
+
+    let label = printer().layout(image, &options)?.into_luma8();
+    let start = (0..label.width())
+      .find(|x| label.get_pixel(*x, 0).0 == [0])
+      .unwrap();
+
+    Ok((label.width(), start))
+  }
+
+  #[test]
+  fn layout_aligns_image() {
+    assert_eq!(layout(10, Some(5.0), Align::Start).unwrap(), (35, 0));
+    assert_eq!(layout(10, Some(5.0), Align::Centre).unwrap(), (35, 12));
+    assert_eq!(layout(10, Some(5.0), Align::End).unwrap(), (35, 25));
+  }
+
+  #[test]
+  fn layout_pads_to_minimum_length() {
+    assert_eq!(layout(10, None, Align::Start).unwrap(), (31, 0));
+    assert_eq!(layout(10, None, Align::End).unwrap(), (31, 21));
+  }
+
+  #[test]
+  fn layout_rejects_bad_lengths() {
+    assert!(matches!(
+      layout(10, Some(1.0), Align::Start),
+      Err(Error::LabelTooShort { .. })
+    ));
+    assert!(matches!(
+      layout(40, Some(5.0), Align::Start),
+      Err(Error::LabelTooLong { .. })
+    ));
+  }
+}
@@ -7,10 +7,17 @@ pub enum MediaType {
   Laminated,
   NonLaminated,
   Fabric,
+  /// HSe 2:1 heat shrink tube.
   HeatShrink,
   Fle,
   FlexibleId,
   Satin,
+  /// HSe 3:1 heat shrink tube.
+  HeatShrink3To1,
+  /// DK continuous length tape.
+  Continuous,
+  /// DK die-cut labels.
+  DieCut,
   Incompatible,
   Unknown(u8),
 }
@@ -22,16 +29,39 @@ impl From<u8> for MediaType {
       0x01 => Self::Laminated,
       0x03 => Self::NonLaminated,
       0x04 => Self::Fabric,
+      0x0A => Self::Continuous,
+      0x0B => Self::DieCut,
       0x11 => Self::HeatShrink,
       0x13 => Self::Fle,
       0x14 => Self::FlexibleId,
       0x15 => Self::Satin,
+      0x17 => Self::HeatShrink3To1,
       0xFF => Self::Incompatible,
       v => Self::Unknown(v),
     }
   }
 }
 
+impl From<MediaType> for u8 {
+  fn from(value: MediaType) -> Self {
+    match value {
+      MediaType::None => 0x00,
+      MediaType::Laminated => 0x01,
+      MediaType::NonLaminated => 0x03,
+      MediaType::Fabric => 0x04,
+      MediaType::Continuous => 0x0A,
+      MediaType::DieCut => 0x0B,
+      MediaType::HeatShrink => 0x11,
+      MediaType::Fle => 0x13,
+      MediaType::FlexibleId => 0x14,
+      MediaType::Satin => 0x15,
+      MediaType::HeatShrink3To1 => 0x17,
+      MediaType::Incompatible => 0xFF,
+      MediaType::Unknown(v) => v,
+    }
+  }
+}
+
 impl MediaType {
   pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
     let (input, media_type) = u8(input)?;
@@ -39,3 +69,24 @@ impl MediaType {
     Ok((input, media_type.into()))
   }
 }
+
+impl MediaType {
+  /// Stable, machine-readable name of the media type.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      MediaType::None => "none",
+      MediaType::Laminated => "laminated",
+      MediaType::NonLaminated => "non_laminated",
+      MediaType::Fabric => "fabric",
+      MediaType::Continuous => "continuous",
+      MediaType::DieCut => "die_cut",
+      MediaType::HeatShrink => "heat_shrink",
+      MediaType::Fle => "fle",
+      MediaType::FlexibleId => "flexible_id",
+      MediaType::Satin => "satin",
+      MediaType::HeatShrink3To1 => "heat_shrink_3_to_1",
+      MediaType::Incompatible => "incompatible",
+      MediaType::Unknown(_) => "unknown",
+    }
+  }
+}
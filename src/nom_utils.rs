@@ -25,10 +25,11 @@ pub fn zero_multi<const N: usize>(input: &[u8]) -> IResult<&[u8], ()> {
   tag_multi([0; N])(input)
 }
 
-pub fn u16(input: &[u8]) -> IResult<&[u8], u16> {
-  nom::number::complete::u16(Endianness::Native)(input)
+/// Multi-byte fields on the wire are little-endian regardless of the host.
+pub fn le_u16(input: &[u8]) -> IResult<&[u8], u16> {
+  nom::number::complete::u16(Endianness::Little)(input)
 }
 
-pub fn u32(input: &[u8]) -> IResult<&[u8], u32> {
-  nom::number::complete::u32(Endianness::Native)(input)
+pub fn le_u32(input: &[u8]) -> IResult<&[u8], u32> {
+  nom::number::complete::u32(Endianness::Little)(input)
 }
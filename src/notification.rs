@@ -0,0 +1,45 @@
+use nom::{IResult, number::complete::u8};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Notification {
+  None,
+  CoverOpen,
+  CoverClosed,
+  CoolingStarted,
+  CoolingFinished,
+  Unknown(u8),
+}
+
+impl From<u8> for Notification {
+  fn from(value: u8) -> Self {
+    match value {
+      0x00 => Notification::None,
+      0x01 => Notification::CoverOpen,
+      0x02 => Notification::CoverClosed,
+      0x03 => Notification::CoolingStarted,
+      0x04 => Notification::CoolingFinished,
+      v => Notification::Unknown(v),
+    }
+  }
+}
+
+impl Notification {
+  pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+    let (input, notification) = u8(input)?;
+
+    Ok((input, notification.into()))
+  }
+
+  /// Stable, machine-readable name of the notification.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      Notification::None => "none",
+      Notification::CoverOpen => "cover_open",
+      Notification::CoverClosed => "cover_closed",
+      Notification::CoolingStarted => "cooling_started",
+      Notification::CoolingFinished => "cooling_finished",
+      Notification::Unknown(_) => "unknown",
+    }
+  }
+}
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use crate::{PrinterType, Quirks};
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpenOptions {
+  pub timeouts: Timeouts,
+  pub status_retry: RetryPolicy,
+  /// Fail with [`crate::Error::ModelMismatch`] when the model code in the
+  /// status reply doesn't match the printer detected from its USB IDs, or
+  /// the one given as [`OpenOptions::model`], rather than just logging a
+  /// warning.
+  pub strict_model_check: bool,
+  /// The printer expected at the other end of a network or serial
+  /// connection, which has no USB IDs to go by. Its status reply is checked
+  /// against it as over USB. Without it, the printer is whatever model the
+  /// reply says it is.
+  pub model: Option<PrinterType>,
+  /// Use these quirks instead of the ones known for the printer.
+  pub quirks: Option<Quirks>,
+}
+
+/// How long each transfer may take before it's cancelled and
+/// [`crate::Error::Timeout`] is returned. `None` waits forever.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timeouts {
+  /// Opening a network connection, for each address the host resolves to.
+  pub connect: Option<Duration>,
+  pub send: Option<Duration>,
+  pub receive: Option<Duration>,
+}
+
+impl Default for Timeouts {
+  fn default() -> Self {
+    Timeouts {
+      connect: Some(Duration::from_secs(5)),
+      send: Some(Duration::from_secs(5)),
+      receive: Some(Duration::from_secs(5)),
+    }
+  }
+}
+
+/// How to retry a status request that the printer doesn't answer, which
+/// happens routinely while it's still waking up or finishing a job.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetryPolicy {
+  /// Total number of requests to send, including the first.
+  pub attempts: u32,
+  /// Wait before the first retry.
+  pub delay: Duration,
+  /// Each later wait is the previous one multiplied by this.
+  pub backoff: f32,
+  /// Give up once this much time has passed, even with attempts left.
+  pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    RetryPolicy {
+      attempts: 10,
+      delay: Duration::from_millis(50),
+      backoff: 1.5,
+      deadline: Some(Duration::from_secs(10)),
+    }
+  }
+}
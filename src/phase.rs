@@ -0,0 +1,56 @@
+use nom::{
+  IResult,
+  number::complete::{be_u16, u8},
+};
+
+/// What the printer is doing, decoded from the phase type and phase number
+/// of a status reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+  /// Idle and ready to receive a job.
+  Editing,
+  Feeding,
+  Printing,
+  CoverOpenWhileReceiving,
+  Unknown {
+    phase_type: u8,
+    phase_number: u16,
+  },
+}
+
+impl From<(u8, u16)> for Phase {
+  fn from((phase_type, phase_number): (u8, u16)) -> Self {
+    match (phase_type, phase_number) {
+      (0x00, 0x0000) => Phase::Editing,
+      (0x00, 0x0001) => Phase::Feeding,
+      (0x01, 0x0000) => Phase::Printing,
+      (0x01, 0x0014) => Phase::CoverOpenWhileReceiving,
+      (phase_type, phase_number) => Phase::Unknown {
+        phase_type,
+        phase_number,
+      },
+    }
+  }
+}
+
+impl Phase {
+  pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+    let (input, phase_type) = u8(input)?;
+    // Unlike the rest of the reply, the phase number is sent high byte first.
+    let (input, phase_number) = be_u16(input)?;
+
+    Ok((input, (phase_type, phase_number).into()))
+  }
+
+  /// Stable, machine-readable name of the phase.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      Phase::Editing => "editing",
+      Phase::Feeding => "feeding",
+      Phase::Printing => "printing",
+      Phase::CoverOpenWhileReceiving => "cover_open_while_receiving",
+      Phase::Unknown { .. } => "unknown",
+    }
+  }
+}
@@ -0,0 +1,142 @@
+use image::{DynamicImage, Rgb, RgbImage};
+
+use crate::{Printer, PrinterType, Status, TapeColor, TapeSize, TextColor};
+
+/// Renders an image the way it would come out of the printer, on the
+/// colour of the loaded tape and in the colour of its ink.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Preview {
+  pub tape_size: TapeSize,
+  /// Width of the printable area, in dots.
+  pub printable_px: u32,
+  pub tape_color: TapeColor,
+  pub text_color: TextColor,
+  pub dpi: u32,
+}
+
+impl Preview {
+  pub fn from_status(status: &Status, ty: PrinterType) -> Self {
+    Preview {
+      tape_size: status.media_width,
+      printable_px: ty.tape_info(status.media_width).px,
+      tape_color: status.tape_color,
+      text_color: status.text_color,
+      dpi: ty.info().dpi,
+    }
+  }
+
+  pub fn from_printer(printer: &Printer) -> Self {
+    Preview::from_status(&printer.status, printer.ty)
+  }
+
+  /// The rendered image is as wide as the source image and as tall as the
+  /// whole tape, with the printable area centred on it.
+  pub fn render(&self, image: &DynamicImage) -> RgbImage {
+    let image = image.to_luma8();
+
+    let tape_px = (self.tape_size.mm() * self.dpi as f32 / 25.4).round() as u32;
+    let printable_px = self.printable_px.min(tape_px);
+
+    let background = Rgb(self.tape_color.rgb());
+    let ink = Rgb(self.text_color.rgb());
+
+    let mut preview = RgbImage::from_pixel(image.width(), tape_px, background);
+
+    // Anything that doesn't fit on the printable area gets cropped, like it
+    // would when printing.
+    let margin = (tape_px - printable_px) / 2;
+    let visible = image.height().min(printable_px);
+    let top = margin + (printable_px - visible) / 2;
+    let skip = (image.height() - visible) / 2;
+
+    for y in 0..visible {
+      for x in 0..image.width() {
+        if image.get_pixel(x, y + skip).0[0] <= 127 {
+          preview.put_pixel(x, y + top, ink);
+        }
+      }
+    }
+
+    preview
+  }
+}
+
+impl Printer {
+  pub fn preview(&self, image: &DynamicImage) -> RgbImage {
+    Preview::from_printer(self).render(image)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::GrayImage;
+
+  use super::*;
+
+  /// Red ink on 12 mm yellow tape, which is 85 dots wide with a 76 dot
+  /// printable area.
+  fn preview() -> Preview {
+    Preview {
+      tape_size: TapeSize::Twelve,
+      printable_px: 76,
+      tape_color: TapeColor::Yellow,
+      text_color: TextColor::Red,
+      dpi: 180,
+    }
+  }
+
+  /// The rows of `preview` that are inked, checking every other pixel is
+  /// tape.
+  fn inked_rows(preview: &RgbImage) -> Vec<u32> {
+    let tape = Rgb(TapeColor::Yellow.rgb());
+    let ink = Rgb(TextColor::Red.rgb());
+
+    (0..preview.height())
+      .filter(|&y| {
+        let row = (0..preview.width())
+          .map(|x| *preview.get_pixel(x, y))
+          .collect::<Vec<_>>();
+        assert!(row.iter().all(|pixel| *pixel == tape || *pixel == ink));
+
+        row.contains(&ink)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn centred_on_tape() {
+    let image = DynamicImage::ImageLuma8(GrayImage::new(10, 20));
+    let rendered = preview().render(&image);
+
+    assert_eq!(rendered.dimensions(), (10, 85));
+
+    // A 4 dot margin, then the 20 rows centred on the printable area.
+    assert_eq!(inked_rows(&rendered), (32..52).collect::<Vec<_>>());
+    assert!(
+      (32..52).all(|y| (0..10).all(|x| *rendered.get_pixel(x, y) == Rgb(TextColor::Red.rgb())))
+    );
+  }
+
+  #[test]
+  fn white_left_as_tape() {
+    let image = DynamicImage::ImageLuma8(GrayImage::from_pixel(10, 20, image::Luma([0xff])));
+
+    assert!(inked_rows(&preview().render(&image)).is_empty());
+  }
+
+  #[test]
+  fn cropped_to_printable_area() {
+    // 100 rows, the top half black, of which only the middle 76 fit.
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(10, 100, |_, y| {
+      image::Luma([if y < 50 { 0x00 } else { 0xff }])
+    }));
+    let rendered = preview().render(&image);
+
+    assert_eq!(rendered.dimensions(), (10, 85));
+
+    // Rows 12 to 49 of the image land on the printable area from dot 4, and
+    // nothing is drawn on the margins.
+    assert_eq!(inked_rows(&rendered), (4..42).collect::<Vec<_>>());
+  }
+}
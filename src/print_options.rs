@@ -0,0 +1,125 @@
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintOptions {
+  pub copies: u32,
+  /// Skip the final feed and cut, so the next label follows on directly.
+  pub chain: bool,
+  /// Cut the tape before printing, on printers that support it.
+  pub precut: bool,
+  /// Dither greyscale images instead of thresholding them.
+  pub dither: bool,
+  /// Pad every label to exactly this length in millimetres, rather than
+  /// the length of the image.
+  pub length: Option<f32>,
+  /// Where the image goes on a label longer than it.
+  pub align: Align,
+  /// Print every line of the image twice as finely along the feed
+  /// direction, on printers with [`crate::PrinterFlags::HighResolution`].
+  pub high_resolution: bool,
+  /// Print in black and red, on printers with
+  /// [`crate::PrinterFlags::TwoColor`] and media that takes both.
+  pub two_color: Option<TwoColor>,
+}
+
+impl Default for PrintOptions {
+  fn default() -> Self {
+    PrintOptions {
+      copies: 1,
+      chain: false,
+      precut: true,
+      dither: false,
+      length: None,
+      align: Align::Start,
+      high_resolution: false,
+      two_color: None,
+    }
+  }
+}
+
+/// Placement of an image along the feed direction of a label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Align {
+  /// Print the image first, then blank tape.
+  #[default]
+  Start,
+  Centre,
+  /// Feed blank tape, then print the image.
+  End,
+}
+
+/// How pixels of a colour image are split between black and red.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TwoColor {
+  /// Pixels that aren't red and whose luma is at or below this print black.
+  pub black: u8,
+  /// Pixels whose red channel exceeds both green and blue by at least this
+  /// much print red.
+  pub red: u8,
+}
+
+impl TwoColor {
+  pub fn is_red(&self, pixel: image::Rgb<u8>) -> bool {
+    let [r, g, b] = pixel.0;
+
+    r.saturating_sub(g.max(b)) >= self.red
+  }
+
+  pub fn is_black(&self, pixel: image::Rgb<u8>) -> bool {
+    !self.is_red(pixel) && image::Pixel::to_luma(&pixel).0[0] <= self.black
+  }
+}
+
+impl Default for TwoColor {
+  fn default() -> Self {
+    TwoColor {
+      black: 127,
+      red: 64,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::Rgb;
+
+  use super::*;
+
+  #[test]
+  fn red() {
+    let two_color = TwoColor::default();
+
+    assert!(two_color.is_red(Rgb([0xff, 0x00, 0x00])));
+    assert!(two_color.is_red(Rgb([0xc8, 0x10, 0x1e])));
+    // Red has to beat green and blue by the threshold, not just lead them.
+    assert!(!two_color.is_red(Rgb([0xff, 0xd0, 0xd0])));
+    assert!(!two_color.is_red(Rgb([0xff, 0x00, 0xff])));
+    assert!(!two_color.is_red(Rgb([0x00, 0x00, 0x00])));
+    assert!(!two_color.is_red(Rgb([0xff, 0xff, 0xff])));
+  }
+
+  #[test]
+  fn black() {
+    let two_color = TwoColor::default();
+
+    assert!(two_color.is_black(Rgb([0x00, 0x00, 0x00])));
+    assert!(two_color.is_black(Rgb([0x7f, 0x7f, 0x7f])));
+    assert!(two_color.is_black(Rgb([0x00, 0x00, 0xff])));
+    assert!(!two_color.is_black(Rgb([0x80, 0x80, 0x80])));
+    assert!(!two_color.is_black(Rgb([0xff, 0xff, 0xff])));
+    // Dark red is red, never black.
+    assert!(!two_color.is_black(Rgb([0x80, 0x00, 0x00])));
+  }
+
+  #[test]
+  fn thresholds() {
+    let two_color = TwoColor {
+      black: 0x20,
+      red: 0x10,
+    };
+
+    assert!(two_color.is_red(Rgb([0x30, 0x20, 0x10])));
+    assert!(!two_color.is_black(Rgb([0x40, 0x40, 0x40])));
+  }
+}
@@ -2,6 +2,14 @@ use std::{collections::HashMap, sync::LazyLock};
 
 use enumflags2::{BitFlags, bitflags};
 
+use crate::{
+  Error,
+  PrintOptions,
+  TapeInfo,
+  TapeSize,
+  registry::{self, CustomIndex, DefinitionError, PrinterDefinition},
+};
+
 static USB_TO_DEVICE: LazyLock<HashMap<(u16, u16), PrinterType>> = LazyLock::new(|| {
   let mut map = HashMap::new();
 
@@ -12,6 +20,27 @@ static USB_TO_DEVICE: LazyLock<HashMap<(u16, u16), PrinterType>> = LazyLock::new
   map
 });
 
+static MODEL_CODE_TO_DEVICE: LazyLock<HashMap<u8, PrinterType>> = LazyLock::new(|| {
+  let mut map = HashMap::new();
+
+  for printer in PrinterType::iter() {
+    let info = printer.info();
+
+    // P-Lite mode is the same printer presenting itself differently over
+    // USB, and reports the same model code.
+    if info.flags.contains(PrinterFlags::PLite) {
+      continue;
+    }
+
+    // Some models share a code, in which case the first one listed wins.
+    if let Some(model_code) = info.model_code {
+      map.entry(model_code).or_insert(printer);
+    }
+  }
+
+  map
+});
+
 static INFO: LazyLock<HashMap<PrinterType, PrinterInfo>> = LazyLock::new(|| {
   let mut map = HashMap::new();
 
@@ -23,7 +52,7 @@ static INFO: LazyLock<HashMap<PrinterType, PrinterInfo>> = LazyLock::new(|| {
 });
 
 #[bitflags]
-#[repr(u8)]
+#[repr(u16)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrinterFlags {
@@ -34,6 +63,32 @@ pub enum PrinterFlags {
   UseInfoCmd = (1 << 4),
   HasPrecut = (1 << 5),
   D460BTMagic = (1 << 6),
+  /// Can double its resolution along the feed direction.
+  HighResolution = (1 << 7),
+  /// Needs the media type and width filled in on the info command.
+  InfoMediaType = (1 << 8),
+  /// Sends raster lines with `g` rather than `G`, as QL printers do.
+  QlRaster = (1 << 9),
+  /// Can print black and red from two raster planes per line.
+  TwoColor = (1 << 10),
+}
+
+impl PrinterFlags {
+  pub const fn name(&self) -> &'static str {
+    match self {
+      PrinterFlags::UnsupportedRaster => "unsupported_raster",
+      PrinterFlags::RasterPackBits => "raster_pack_bits",
+      PrinterFlags::PLite => "p_lite",
+      PrinterFlags::P700Init => "p700_init",
+      PrinterFlags::UseInfoCmd => "use_info_cmd",
+      PrinterFlags::HasPrecut => "has_precut",
+      PrinterFlags::D460BTMagic => "d460bt_magic",
+      PrinterFlags::HighResolution => "high_resolution",
+      PrinterFlags::InfoMediaType => "info_media_type",
+      PrinterFlags::QlRaster => "ql_raster",
+      PrinterFlags::TwoColor => "two_color",
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -41,9 +96,81 @@ pub enum PrinterFlags {
 pub struct PrinterInfo {
   pub vendor_id: u16,
   pub product_id: u16,
+  /// The model code reported in byte 4 of the status reply, where known.
+  pub model_code: Option<u8>,
   pub max_px: u32,
   pub dpi: u32,
+  /// Shortest label the printer will produce, in millimetres. Shorter
+  /// images are padded out to it.
+  pub min_length: f32,
+  /// Tape between the print head and the cutter, in millimetres, which is
+  /// fed out after the last label of a job to cut it off, unless the job is
+  /// chained to the next one.
+  pub leader: f32,
+  /// Blank tape fed out at each cut, in millimetres.
+  pub cut_margin: f32,
+  /// Invalidate bytes sent ahead of initializing the printer, to flush out
+  /// whatever an interrupted job left behind.
+  pub invalidate: u32,
   pub flags: BitFlags<PrinterFlags>,
+  pub quirks: Quirks,
+}
+
+/// Brother's QL raster command reference gives 12.7 mm as the shortest
+/// length of continuous tape, and DK die-cut labels are no shorter.
+const QL_MIN_LENGTH: f32 = 12.7;
+
+/// The QL-500 and QL-550 won't print less than 295 dots of label, just
+/// under 25 mm.
+const QL_500_MIN_LENGTH: f32 = 295.0 * 25.4 / 300.0;
+
+impl Default for PrinterInfo {
+  /// A 180 dpi, 128 dot P-touch, which most printers are. The shortest
+  /// label is the 4.4 mm the P-touch raster command references give as
+  /// their minimum print length, the cutter sits about an inch past the
+  /// head, and each cut feeds the 14 dot margin P-touch printers default
+  /// to. P-touch printers are invalidated with 100 bytes.
+  fn default() -> Self {
+    PrinterInfo {
+      vendor_id: 0x04f9,
+      product_id: 0,
+      model_code: None,
+      max_px: 128,
+      dpi: 180,
+      min_length: 4.4,
+      leader: 25.4,
+      cut_margin: 2.0,
+      invalidate: 100,
+      flags: BitFlags::empty(),
+      quirks: Quirks::default(),
+    }
+  }
+}
+
+/// Workarounds for printers that don't quite behave like the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Quirks {
+  /// Dots at the start of the head that must be left blank. Tape that would
+  /// start on them is printed starting just after them instead.
+  pub leading_px: u32,
+  /// Blank raster lines sent ahead of every label.
+  pub padding: u32,
+  /// Longest label the printer manages before cutting, in millimetres.
+  pub max_length: Option<f32>,
+}
+
+impl PrinterInfo {
+  /// Number of raster lines covering `mm` of tape.
+  pub fn lines(&self, mm: f32) -> u32 {
+    (mm * self.dpi as f32 / 25.4).round() as u32
+  }
+
+  /// Length of tape covered by `lines` raster lines, in millimetres.
+  pub fn mm(&self, lines: u32) -> f32 {
+    lines as f32 * 25.4 / self.dpi as f32
+  }
 }
 
 #[allow(non_camel_case_types)]
@@ -64,8 +191,10 @@ pub enum PrinterType {
   /// Notes about the PT-2730: was reported to need 48px whitespace within png-images before content is actually printed - can not check this
   PT_2730,
   /// Note about the PT-E500: was reported by Jesse Becker with the remark that it also needs some padding (white pixels)
+  /// How much isn't known, so none is added; set [`Quirks::padding`] with [`crate::OpenOptions::quirks`] if it's needed.
   PT_H500,
   /// Note about the PT-E500: was reported by Jesse Becker with the remark that it also needs some padding (white pixels)
+  /// How much isn't known, so none is added; set [`Quirks::padding`] with [`crate::OpenOptions::quirks`] if it's needed.
   PT_E500,
   PT_P700,
   PT_P750W,
@@ -84,13 +213,55 @@ pub enum PrinterType {
   /// 3,5/6/9/12/18 mm TZe Tapes, 12mm and 18mm tested
   /// 5,2/9/11,2 mm HSe heat shrink tubes not tested, probably requiring extension of struct _pt_tape_info
   PT_E310BT,
+  PT_E550W,
+  PT_P900W,
+  PT_P950NW,
+  PT_P900,
+  PT_P910BT,
+  PT_E560BT,
+  QL_500,
+  QL_550,
+  QL_570,
+  QL_700,
+  QL_710W,
+  QL_720NW,
+  QL_800,
+  QL_810W,
+  QL_820NWB,
+  /// A printer registered at runtime with [`PrinterType::register`].
+  #[strum(disabled)]
+  #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+  Custom(CustomIndex),
 }
 
 impl PrinterType {
+  /// Registered custom printers take precedence over built-in ones.
   pub fn from_usb(vendor: u16, product: u16) -> Option<Self> {
-    USB_TO_DEVICE.get(&(vendor, product)).cloned()
+    registry::find(|custom| custom.vendor_id == vendor && custom.product_id == product)
+      .or_else(|| USB_TO_DEVICE.get(&(vendor, product)).cloned())
   }
 
+  pub fn from_model_code(model_code: u8) -> Option<Self> {
+    registry::find(|custom| custom.model_code == Some(model_code))
+      .or_else(|| MODEL_CODE_TO_DEVICE.get(&model_code).cloned())
+  }
+
+  /// Adds a printer this crate doesn't know about, or replaces the one
+  /// registered under the same name. Definitions that fail
+  /// [`PrinterDefinition::validate`] aren't registered.
+  pub fn register(definition: PrinterDefinition) -> Result<Self, DefinitionError> {
+    registry::register(definition)
+  }
+
+  /// The definition of a custom printer.
+  pub fn definition(&self) -> Option<PrinterDefinition> {
+    match self {
+      PrinterType::Custom(index) => Some(registry::get(*index)),
+      _ => None,
+    }
+  }
+
+  /// Built-in printers, not including custom ones.
   pub fn iter() -> impl Iterator<Item = Self> {
     <Self as strum::IntoEnumIterator>::iter()
   }
@@ -99,182 +270,730 @@ impl PrinterType {
     INFO.iter()
   }
 
+  /// A stable, machine-readable name for the model. Custom printers are
+  /// all called `custom`; their own name is on their [`definition`].
+  ///
+  /// [`definition`]: PrinterType::definition
+  pub const fn name(&self) -> &'static str {
+    match self {
+      PrinterType::PT_9200DX => "pt_9200dx",
+      PrinterType::PT_2300 => "pt_2300",
+      PrinterType::PT_2420PC => "pt_2420pc",
+      PrinterType::PT_2450PC => "pt_2450pc",
+      PrinterType::PT_1950 => "pt_1950",
+      PrinterType::PT_2700 => "pt_2700",
+      PrinterType::PT_1230PC => "pt_1230pc",
+      PrinterType::PT_2430PC => "pt_2430pc",
+      PrinterType::PT_1230PC_PLite => "pt_1230pc_plite",
+      PrinterType::PT_2430PC_PLite => "pt_2430pc_plite",
+      PrinterType::PT_2730 => "pt_2730",
+      PrinterType::PT_H500 => "pt_h500",
+      PrinterType::PT_E500 => "pt_e500",
+      PrinterType::PT_P700 => "pt_p700",
+      PrinterType::PT_P750W => "pt_p750w",
+      PrinterType::PT_P700_PLite => "pt_p700_plite",
+      PrinterType::PT_P750W_PLite => "pt_p750w_plite",
+      PrinterType::PT_D410 => "pt_d410",
+      PrinterType::PT_D450 => "pt_d450",
+      PrinterType::PT_D460BT => "pt_d460bt",
+      PrinterType::PT_D600 => "pt_d600",
+      PrinterType::PT_D610BT => "pt_d610bt",
+      PrinterType::PT_P710BT => "pt_p710bt",
+      PrinterType::PT_E310BT => "pt_e310bt",
+      PrinterType::PT_E550W => "pt_e550w",
+      PrinterType::PT_P900W => "pt_p900w",
+      PrinterType::PT_P950NW => "pt_p950nw",
+      PrinterType::PT_P900 => "pt_p900",
+      PrinterType::PT_P910BT => "pt_p910bt",
+      PrinterType::PT_E560BT => "pt_e560bt",
+      PrinterType::QL_500 => "ql_500",
+      PrinterType::QL_550 => "ql_550",
+      PrinterType::QL_570 => "ql_570",
+      PrinterType::QL_700 => "ql_700",
+      PrinterType::QL_710W => "ql_710w",
+      PrinterType::QL_720NW => "ql_720nw",
+      PrinterType::QL_800 => "ql_800",
+      PrinterType::QL_810W => "ql_810w",
+      PrinterType::QL_820NWB => "ql_820nwb",
+      PrinterType::Custom(_) => "custom",
+    }
+  }
+
+  /// Number of raster lines in a label printed from an image `width` lines
+  /// long, after padding it to the requested and minimum lengths.
+  pub fn label_lines(&self, width: u32, options: &PrintOptions) -> Result<u32, Error> {
+    let info = self.info();
+
+    let lines = match options.length {
+      Some(length) if length < info.min_length => Err(Error::LabelTooShort {
+        length,
+        minimum: info.min_length,
+      })?,
+      Some(length) if info.lines(length) < width => Err(Error::LabelTooLong {
+        length,
+        image: info.mm(width),
+      })?,
+      Some(length) => info.lines(length),
+      None => width,
+    };
+
+    Ok(lines.max(info.lines(info.min_length)))
+  }
+
+  /// Where the printable area of a tape lies under this printer's head.
+  pub fn tape_info(&self, size: TapeSize) -> TapeInfo {
+    let info = self.info();
+
+    if let PrinterType::Custom(index) = self
+      && let Some(tape) = registry::with(*index, |custom| custom.tape_info(size))
+    {
+      return tape;
+    }
+
+    if let Some(ql) = size.ql_info() {
+      return ql;
+    }
+
+    if let Some((px, offset)) = self.pins(size) {
+      // The 180 dpi margins don't carry over to a finer head, so work them
+      // out from how much of the tape the printable area covers.
+      let margins = (size.mm() - px as f32 * 25.4 / info.dpi as f32) / 2.0;
+
+      return TapeInfo {
+        px,
+        margins: margins.max(0.0),
+        offset,
+      };
+    }
+
+    let tape = size.info();
+
+    // `TapeSize::info` is already laid out for a 180 dpi, 128 dot head.
+    if info.dpi == 180 && info.max_px == 128 {
+      return TapeInfo {
+        px: tape.px.min(info.max_px),
+        ..tape
+      };
+    }
+
+    // Any other head gets the 180 dpi geometry scaled to its resolution,
+    // as far as the head reaches, and centred under it.
+    let px = (tape.px * info.dpi / 180).min(info.max_px);
+
+    TapeInfo {
+      px,
+      margins: tape.margins,
+      offset: (info.max_px - px) / 2,
+    }
+  }
+
+  /// The width of the printable area and the first pin over it, for heads
+  /// that Brother gives the layout of tape by tape.
+  fn pins(&self, size: TapeSize) -> Option<(u32, u32)> {
+    let pins = match (self, size) {
+      // The PT-9200DX centres tape under its 384 dot, 360 dpi head.
+      (PrinterType::PT_9200DX, TapeSize::ThreePointFive) => (48, 168),
+      (PrinterType::PT_9200DX, TapeSize::Six) => (64, 160),
+      (PrinterType::PT_9200DX, TapeSize::Nine) => (106, 139),
+      (PrinterType::PT_9200DX, TapeSize::Twelve) => (150, 117),
+      (PrinterType::PT_9200DX, TapeSize::Eighteen) => (234, 75),
+      (PrinterType::PT_9200DX, TapeSize::TwentyFour) => (320, 32),
+      (PrinterType::PT_9200DX, TapeSize::ThirtySix) => (384, 0),
+      // The P900 series doesn't centre tape under its 560 dot head.
+      (
+        PrinterType::PT_P900
+        | PrinterType::PT_P900W
+        | PrinterType::PT_P950NW
+        | PrinterType::PT_P910BT,
+        size,
+      ) => match size {
+        TapeSize::ThreePointFive => (48, 248),
+        TapeSize::Six => (64, 240),
+        TapeSize::Nine => (106, 219),
+        TapeSize::Twelve => (150, 197),
+        TapeSize::Eighteen => (234, 155),
+        TapeSize::TwentyFour => (320, 113),
+        TapeSize::ThirtySix => (454, 61),
+        // Heat shrink tube and FLe labels are twice their 180 dpi width,
+        // and sit like tape up to 24 mm does, 8 pins short of centred.
+        TapeSize::HeatShrink5_8 => (56, 244),
+        TapeSize::HeatShrink8_8 => (96, 224),
+        TapeSize::HeatShrink11_7 => (132, 206),
+        TapeSize::HeatShrink17_7 => (212, 166),
+        TapeSize::HeatShrink23_6 => (256, 144),
+        TapeSize::HeatShrink5_2 => (44, 250),
+        TapeSize::HeatShrink9_0 => (96, 224),
+        TapeSize::HeatShrink11_2 => (124, 210),
+        TapeSize::HeatShrink21_0 => (224, 160),
+        TapeSize::HeatShrink31_0 => (256, 144),
+        TapeSize::Fle21 => (224, 160),
+        _ => return None,
+      },
+      _ => return None,
+    };
+
+    Some(pins)
+  }
+
   pub fn info(&self) -> PrinterInfo {
     match self {
+      PrinterType::Custom(index) => registry::with(*index, PrinterDefinition::info),
       PrinterType::PT_9200DX => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2001,
+        model_code: None,
         max_px: 384,
         dpi: 360,
         flags: PrinterFlags::RasterPackBits | PrinterFlags::HasPrecut,
+        ..Default::default()
       },
       PrinterType::PT_2300 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2004,
+        model_code: None,
         max_px: 112,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits | PrinterFlags::HasPrecut,
+        ..Default::default()
       },
       PrinterType::PT_2420PC => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2007,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits.into(),
+        ..Default::default()
       },
       PrinterType::PT_2450PC => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2011,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits.into(),
+        ..Default::default()
       },
       PrinterType::PT_1950 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2019,
+        model_code: None,
         max_px: 112,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits.into(),
+        ..Default::default()
       },
       PrinterType::PT_2700 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x201f,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::HasPrecut.into(),
+        ..Default::default()
       },
       PrinterType::PT_1230PC => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x202c,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: BitFlags::empty(),
+        quirks: Quirks {
+          leading_px: 32,
+          ..Default::default()
+        },
+        ..Default::default()
       },
       PrinterType::PT_2430PC => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x202d,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: BitFlags::empty(),
+        ..Default::default()
       },
       PrinterType::PT_1230PC_PLite => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2030,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::PLite.into(),
+        ..Default::default()
       },
       PrinterType::PT_2430PC_PLite => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2031,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::PLite.into(),
+        ..Default::default()
       },
       PrinterType::PT_2730 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2041,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: BitFlags::empty(),
+        quirks: Quirks {
+          padding: 48,
+          ..Default::default()
+        },
+        ..Default::default()
       },
       PrinterType::PT_H500 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x205e,
+        model_code: Some(0x64),
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits.into(),
+        ..Default::default()
       },
       PrinterType::PT_E500 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x205f,
+        model_code: Some(0x65),
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits.into(),
+        ..Default::default()
       },
       PrinterType::PT_P700 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2061,
+        model_code: Some(0x67),
         max_px: 128,
         dpi: 180,
-        flags: PrinterFlags::RasterPackBits | PrinterFlags::P700Init | PrinterFlags::HasPrecut,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution,
+        ..Default::default()
       },
       PrinterType::PT_P750W => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2062,
+        model_code: Some(0x68),
         max_px: 128,
         dpi: 180,
-        flags: PrinterFlags::RasterPackBits | PrinterFlags::P700Init,
+        flags: PrinterFlags::RasterPackBits | PrinterFlags::P700Init | PrinterFlags::HighResolution,
+        ..Default::default()
       },
       PrinterType::PT_P700_PLite => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2064,
+        model_code: Some(0x67),
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::PLite.into(),
+        ..Default::default()
       },
       PrinterType::PT_P750W_PLite => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2065,
+        model_code: Some(0x68),
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::PLite.into(),
+        ..Default::default()
       },
       PrinterType::PT_D410 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x20df,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::UseInfoCmd | PrinterFlags::HasPrecut | PrinterFlags::D460BTMagic,
+        ..Default::default()
       },
       PrinterType::PT_D450 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2073,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::UseInfoCmd.into(),
+        ..Default::default()
       },
       PrinterType::PT_D460BT => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x20e0,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::P700Init
           | PrinterFlags::UseInfoCmd
           | PrinterFlags::HasPrecut
           | PrinterFlags::D460BTMagic,
+        ..Default::default()
       },
       PrinterType::PT_D600 => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2074,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::RasterPackBits.into(),
+        quirks: Quirks {
+          max_length: Some(73.0),
+          ..Default::default()
+        },
+        ..Default::default()
       },
       PrinterType::PT_D610BT => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x20e1,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::P700Init
           | PrinterFlags::UseInfoCmd
           | PrinterFlags::HasPrecut
           | PrinterFlags::D460BTMagic,
+        ..Default::default()
       },
       PrinterType::PT_P710BT => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x20af,
+        model_code: Some(0x76),
         max_px: 128,
         dpi: 180,
-        flags: PrinterFlags::RasterPackBits | PrinterFlags::HasPrecut,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution,
+        ..Default::default()
       },
       PrinterType::PT_E310BT => PrinterInfo {
         vendor_id: 0x04f9,
         product_id: 0x2201,
+        model_code: None,
         max_px: 128,
         dpi: 180,
         flags: PrinterFlags::P700Init | PrinterFlags::UseInfoCmd | PrinterFlags::D460BTMagic,
+        ..Default::default()
       },
+      PrinterType::PT_E550W => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2060,
+        model_code: Some(0x66),
+        max_px: 128,
+        dpi: 180,
+        flags: PrinterFlags::RasterPackBits | PrinterFlags::P700Init | PrinterFlags::HighResolution,
+        ..Default::default()
+      },
+      PrinterType::PT_P900W => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2085,
+        model_code: Some(0x69),
+        max_px: 560,
+        dpi: 360,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution,
+        ..Default::default()
+      },
+      PrinterType::PT_P950NW => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2086,
+        model_code: Some(0x70),
+        max_px: 560,
+        dpi: 360,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution,
+        ..Default::default()
+      },
+      PrinterType::PT_P900 => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2083,
+        model_code: Some(0x71),
+        max_px: 560,
+        dpi: 360,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution,
+        ..Default::default()
+      },
+      PrinterType::PT_P910BT => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x20c7,
+        model_code: Some(0x78),
+        max_px: 560,
+        dpi: 360,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution,
+        ..Default::default()
+      },
+      PrinterType::PT_E560BT => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2203,
+        model_code: None,
+        max_px: 128,
+        dpi: 180,
+        flags: PrinterFlags::P700Init | PrinterFlags::UseInfoCmd | PrinterFlags::D460BTMagic,
+        ..Default::default()
+      },
+      PrinterType::QL_500 => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2015,
+        model_code: Some(0x4f),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_500_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 200,
+        flags: PrinterFlags::UseInfoCmd | PrinterFlags::InfoMediaType | PrinterFlags::QlRaster,
+        ..Default::default()
+      },
+      PrinterType::QL_550 => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2016,
+        model_code: Some(0x4f),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_500_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 200,
+        flags: PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::QlRaster,
+        ..Default::default()
+      },
+      PrinterType::QL_570 => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2028,
+        model_code: Some(0x32),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 200,
+        flags: PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster,
+        ..Default::default()
+      },
+      PrinterType::QL_700 => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2042,
+        model_code: Some(0x35),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 200,
+        flags: PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster,
+        ..Default::default()
+      },
+      PrinterType::QL_710W => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2043,
+        model_code: Some(0x36),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 200,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster,
+        ..Default::default()
+      },
+      PrinterType::QL_720NW => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x2044,
+        model_code: Some(0x37),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 200,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster,
+        ..Default::default()
+      },
+      PrinterType::QL_800 => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x209b,
+        model_code: Some(0x38),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 400,
+        flags: PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster
+          | PrinterFlags::TwoColor,
+        ..Default::default()
+      },
+      PrinterType::QL_810W => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x209c,
+        model_code: Some(0x39),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 400,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster
+          | PrinterFlags::TwoColor,
+        ..Default::default()
+      },
+      PrinterType::QL_820NWB => PrinterInfo {
+        vendor_id: 0x04f9,
+        product_id: 0x209d,
+        model_code: Some(0x41),
+        max_px: 720,
+        dpi: 300,
+        min_length: QL_MIN_LENGTH,
+        leader: 3.0,
+        cut_margin: 3.0,
+        invalidate: 400,
+        flags: PrinterFlags::RasterPackBits
+          | PrinterFlags::P700Init
+          | PrinterFlags::UseInfoCmd
+          | PrinterFlags::InfoMediaType
+          | PrinterFlags::HasPrecut
+          | PrinterFlags::HighResolution
+          | PrinterFlags::QlRaster
+          | PrinterFlags::TwoColor,
+        ..Default::default()
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn lines(ty: PrinterType, width: u32, length: Option<f32>) -> Result<u32, Error> {
+    let options = PrintOptions {
+      length,
+      ..Default::default()
+    };
+
+    ty.label_lines(width, &options)
+  }
+
+  #[test]
+  fn label_lines_without_length() {
+    assert_eq!(lines(PrinterType::PT_P700, 100, None).unwrap(), 100);
+    // Padded out to 4.4 mm, or 12.7 mm on a QL.
+    assert_eq!(lines(PrinterType::PT_P700, 10, None).unwrap(), 31);
+    assert_eq!(lines(PrinterType::QL_800, 10, None).unwrap(), 150);
+  }
+
+  #[test]
+  fn label_lines_with_length() {
+    assert_eq!(lines(PrinterType::PT_P700, 10, Some(10.0)).unwrap(), 71);
+    assert_eq!(lines(PrinterType::PT_P900, 10, Some(10.0)).unwrap(), 142);
+  }
+
+  #[test]
+  fn label_lines_too_short() {
+    assert!(matches!(
+      lines(PrinterType::PT_P700, 10, Some(2.0)),
+      Err(Error::LabelTooShort { minimum: 4.4, .. })
+    ));
+    assert!(matches!(
+      lines(PrinterType::QL_800, 10, Some(10.0)),
+      Err(Error::LabelTooShort { minimum: 12.7, .. })
+    ));
+  }
+
+  #[test]
+  fn ql_models() {
+    use PrinterType::*;
+
+    // Model, compressed raster lines, shortest label in dots and
+    // invalidate bytes, from Brother's QL raster command references.
+    let models = [
+      (QL_500, false, 295, 200),
+      (QL_550, false, 295, 200),
+      (QL_570, false, 150, 200),
+      (QL_700, false, 150, 200),
+      (QL_710W, true, 150, 200),
+      (QL_720NW, true, 150, 200),
+      (QL_800, false, 150, 400),
+      (QL_810W, true, 150, 400),
+      (QL_820NWB, true, 150, 400),
+    ];
+
+    for (ty, packbits, min_lines, invalidate) in models {
+      let info = ty.info();
+
+      assert_eq!(
+        info.flags.contains(PrinterFlags::RasterPackBits),
+        packbits,
+        "{ty:?}"
+      );
+      assert_eq!(info.lines(info.min_length), min_lines, "{ty:?}");
+      assert_eq!(info.invalidate, invalidate, "{ty:?}");
     }
+
+    // P-touch printers are invalidated with 100 bytes.
+    assert_eq!(PT_P700.info().invalidate, 100);
+  }
+
+  #[test]
+  fn label_lines_too_long() {
+    assert!(matches!(
+      lines(PrinterType::PT_P700, 100, Some(5.0)),
+      Err(Error::LabelTooLong { length: 5.0, .. })
+    ));
   }
 }
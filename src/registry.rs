@@ -0,0 +1,246 @@
+//! Printers defined at runtime, for models this crate doesn't know about
+//! yet.
+
+use std::sync::RwLock;
+
+use enumflags2::BitFlags;
+
+use crate::{PrinterFlags, PrinterInfo, PrinterType, Quirks, TapeInfo, TapeSize};
+
+static REGISTRY: RwLock<Vec<PrinterDefinition>> = RwLock::new(Vec::new());
+
+/// The widest head a single uncompressed run of PackBits covers.
+const MAX_PACKBITS_PX: u32 = 128 * 8;
+
+/// A printer model, as registered with [`PrinterType::register`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PrinterDefinition {
+  pub name: String,
+  pub vendor_id: u16,
+  pub product_id: u16,
+  /// The model code reported in byte 4 of the status reply, where known.
+  pub model_code: Option<u8>,
+  pub max_px: u32,
+  pub dpi: u32,
+  /// Shortest label the printer will produce, in millimetres.
+  pub min_length: f32,
+  /// Tape between the print head and the cutter, in millimetres.
+  pub leader: f32,
+  /// Blank tape fed out at each cut, in millimetres.
+  pub cut_margin: f32,
+  /// Invalidate bytes sent ahead of initializing the printer.
+  pub invalidate: u32,
+  pub flags: Vec<PrinterFlags>,
+  pub quirks: Quirks,
+  /// Geometry of particular tapes. Tapes not listed here are scaled from
+  /// the 180 dpi geometry and centred under the head.
+  pub tapes: Vec<TapeGeometry>,
+}
+
+/// The index of a registered printer. Only the registry hands these out,
+/// so every [`PrinterType::Custom`] has a definition behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct CustomIndex(u16);
+
+/// Why a [`PrinterDefinition`] was refused.
+#[derive(Debug, thiserror::Error)]
+pub enum DefinitionError {
+  #[cfg(feature = "toml")]
+  #[error(transparent)]
+  Toml(#[from] toml::de::Error),
+  #[error("{name}: a head {max_px} dots wide isn't a whole number of bytes")]
+  HeadWidth { name: String, max_px: u32 },
+  #[error("{name}: a head {max_px} dots wide is too wide for compressed raster lines")]
+  HeadTooWide { name: String, max_px: u32 },
+  #[error("{name}: {tape:?} from dot {offset} for {px} dots doesn't fit on the {max_px} dot head")]
+  TapeOffHead {
+    name: String,
+    tape: TapeSize,
+    offset: u32,
+    px: u32,
+    max_px: u32,
+  },
+}
+
+/// Where a tape lies under a custom printer's head.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TapeGeometry {
+  /// Tape widths alone don't tell heat shrink tube from regular tape, so
+  /// geometry is given per [`TapeSize`].
+  pub tape: TapeSize,
+  pub px: u32,
+  pub offset: u32,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub margins: f32,
+}
+
+impl Default for PrinterDefinition {
+  fn default() -> Self {
+    let info = PrinterInfo::default();
+
+    PrinterDefinition {
+      name: String::new(),
+      vendor_id: info.vendor_id,
+      product_id: info.product_id,
+      model_code: info.model_code,
+      max_px: info.max_px,
+      dpi: info.dpi,
+      min_length: info.min_length,
+      leader: info.leader,
+      cut_margin: info.cut_margin,
+      invalidate: info.invalidate,
+      flags: info.flags.iter().collect(),
+      quirks: info.quirks,
+      tapes: Vec::new(),
+    }
+  }
+}
+
+impl PrinterDefinition {
+  pub fn info(&self) -> PrinterInfo {
+    PrinterInfo {
+      vendor_id: self.vendor_id,
+      product_id: self.product_id,
+      model_code: self.model_code,
+      max_px: self.max_px,
+      dpi: self.dpi,
+      min_length: self.min_length,
+      leader: self.leader,
+      cut_margin: self.cut_margin,
+      invalidate: self.invalidate,
+      flags: self.flags.iter().copied().collect::<BitFlags<_>>(),
+      quirks: self.quirks,
+    }
+  }
+
+  pub fn tape_info(&self, size: TapeSize) -> Option<TapeInfo> {
+    self
+      .tapes
+      .iter()
+      .find(|tape| tape.tape == size)
+      .map(|tape| TapeInfo {
+        px: tape.px,
+        margins: tape.margins,
+        offset: tape.offset,
+      })
+  }
+
+  /// Checks that raster lines for this printer can be built: the head is a
+  /// whole number of bytes, and every tape lies on it.
+  pub fn validate(&self) -> Result<(), DefinitionError> {
+    if self.max_px == 0 || !self.max_px.is_multiple_of(8) {
+      return Err(DefinitionError::HeadWidth {
+        name: self.name.clone(),
+        max_px: self.max_px,
+      });
+    }
+
+    // Compressed lines are sent as a single literal run, which holds at
+    // most 128 bytes.
+    if self.flags.contains(&PrinterFlags::RasterPackBits) && self.max_px > MAX_PACKBITS_PX {
+      return Err(DefinitionError::HeadTooWide {
+        name: self.name.clone(),
+        max_px: self.max_px,
+      });
+    }
+
+    for tape in &self.tapes {
+      if tape
+        .offset
+        .checked_add(tape.px)
+        .is_none_or(|end| end > self.max_px)
+      {
+        return Err(DefinitionError::TapeOffHead {
+          name: self.name.clone(),
+          tape: tape.tape,
+          offset: tape.offset,
+          px: tape.px,
+          max_px: self.max_px,
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Reads definitions from a TOML document with a `[[printer]]` table for
+  /// each model, checking each as [`PrinterDefinition::validate`] does.
+  #[cfg(feature = "toml")]
+  pub fn from_toml(toml: &str) -> Result<Vec<Self>, DefinitionError> {
+    #[derive(serde::Deserialize)]
+    struct Definitions {
+      printer: Vec<PrinterDefinition>,
+    }
+
+    let definitions = toml::from_str::<Definitions>(toml)?.printer;
+
+    for definition in &definitions {
+      definition.validate()?;
+    }
+
+    Ok(definitions)
+  }
+
+  /// Writes definitions out in the form [`PrinterDefinition::from_toml`]
+  /// reads.
+  #[cfg(feature = "toml")]
+  pub fn to_toml(definitions: &[Self]) -> Result<String, toml::ser::Error> {
+    #[derive(serde::Serialize)]
+    struct Definitions<'a> {
+      printer: &'a [PrinterDefinition],
+    }
+
+    toml::to_string(&Definitions {
+      printer: definitions,
+    })
+  }
+}
+
+pub(crate) fn get(index: CustomIndex) -> PrinterDefinition {
+  with(index, PrinterDefinition::clone)
+}
+
+/// Runs `f` on a registered definition without cloning it.
+pub(crate) fn with<T>(index: CustomIndex, f: impl FnOnce(&PrinterDefinition) -> T) -> T {
+  let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+
+  // Definitions are only ever replaced, never removed, so an index handed
+  // out by `register` or `find` stays good.
+  f(&registry[index.0 as usize])
+}
+
+pub(crate) fn register(definition: PrinterDefinition) -> Result<PrinterType, DefinitionError> {
+  definition.validate()?;
+
+  let mut registry = REGISTRY.write().unwrap_or_else(|e| e.into_inner());
+
+  let index = match registry
+    .iter()
+    .position(|registered| registered.name == definition.name)
+  {
+    Some(index) => {
+      registry[index] = definition;
+      index
+    },
+    None => {
+      registry.push(definition);
+      registry.len() - 1
+    },
+  };
+
+  Ok(PrinterType::Custom(CustomIndex(index as u16)))
+}
+
+pub(crate) fn find(f: impl Fn(&PrinterDefinition) -> bool) -> Option<PrinterType> {
+  REGISTRY
+    .read()
+    .unwrap_or_else(|e| e.into_inner())
+    .iter()
+    .position(f)
+    .map(|index| PrinterType::Custom(CustomIndex(index as u16)))
+}
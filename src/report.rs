@@ -0,0 +1,112 @@
+//! Stable JSON-friendly documents describing printers and their status.
+//!
+//! Unlike the types they are built from, these flatten enums down to their
+//! machine-readable names and report tape widths in millimetres, so their
+//! serialised form doesn't change when variants are added or renamed.
+
+use serde::Serialize;
+
+use crate::{ConnectedPrinter, Printer, PrinterType, Status};
+
+/// Built-in models go by [`PrinterType::name`], custom ones by the name they
+/// were registered under.
+fn model_name(ty: PrinterType) -> String {
+  match ty.definition() {
+    Some(definition) => definition.name,
+    None => ty.name().to_string(),
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceReport {
+  pub model: String,
+  pub bus_number: u8,
+  pub device_address: u8,
+  pub serial_number: Option<String>,
+}
+
+impl From<&ConnectedPrinter> for DeviceReport {
+  fn from(printer: &ConnectedPrinter) -> Self {
+    DeviceReport {
+      model: model_name(printer.ty),
+      bus_number: printer.bus_number,
+      device_address: printer.device_address,
+      serial_number: printer.serial_number.clone(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrinterReport {
+  pub model: String,
+  pub vendor_id: u16,
+  pub product_id: u16,
+  pub dpi: u32,
+  pub max_px: u32,
+  pub flags: Vec<&'static str>,
+}
+
+impl From<PrinterType> for PrinterReport {
+  fn from(ty: PrinterType) -> Self {
+    let info = ty.info();
+
+    PrinterReport {
+      model: model_name(ty),
+      vendor_id: info.vendor_id,
+      product_id: info.product_id,
+      dpi: info.dpi,
+      max_px: info.max_px,
+      flags: info.flags.iter().map(|flag| flag.name()).collect(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+  pub model_code: u8,
+  pub model: Option<String>,
+  pub status: &'static str,
+  pub error: u16,
+  pub errors: Vec<&'static str>,
+  pub media_type: &'static str,
+  pub tape_width_mm: f32,
+  pub tape_color: &'static str,
+  pub text_color: &'static str,
+  pub mode: Vec<&'static str>,
+  pub phase: &'static str,
+  pub notification: &'static str,
+}
+
+impl From<&Status> for StatusReport {
+  fn from(status: &Status) -> Self {
+    StatusReport {
+      model_code: status.model,
+      model: status.printer_type().map(model_name),
+      status: status.status_type.name(),
+      error: status.error.bits(),
+      errors: status.error.iter().map(|error| error.name()).collect(),
+      media_type: status.media_type.name(),
+      tape_width_mm: status.media_width.mm(),
+      tape_color: status.tape_color.name(),
+      text_color: status.text_color.name(),
+      mode: status.mode.iter().map(|mode| mode.name()).collect(),
+      phase: status.phase.name(),
+      notification: status.notification.name(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+  pub printer: PrinterReport,
+  pub status: StatusReport,
+}
+
+impl From<&Printer> for Report {
+  fn from(printer: &Printer) -> Self {
+    Report {
+      printer: printer.ty.into(),
+      status: (&printer.status).into(),
+    }
+  }
+}
@@ -0,0 +1,85 @@
+use enumflags2::{BitFlags, bitflags};
+use nom::IResult;
+
+use crate::nom_utils::le_u16;
+
+/// The two error information bytes of a status reply, with the first byte
+/// in the low half.
+#[bitflags]
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorFlags {
+  NoMedia = (1 << 0),
+  EndOfMedia = (1 << 1),
+  CutterJam = (1 << 2),
+  WeakBatteries = (1 << 3),
+  PrinterInUse = (1 << 4),
+  PrinterTurnedOff = (1 << 5),
+  HighVoltageAdapter = (1 << 6),
+  FanMotorError = (1 << 7),
+  ReplaceMedia = (1 << 8),
+  ExpansionBufferFull = (1 << 9),
+  CommunicationError = (1 << 10),
+  CommunicationBufferFull = (1 << 11),
+  CoverOpen = (1 << 12),
+  Overheating = (1 << 13),
+  BlackMarkingNotDetected = (1 << 14),
+  SystemError = (1 << 15),
+}
+
+impl ErrorFlags {
+  pub fn parse(input: &[u8]) -> IResult<&[u8], BitFlags<Self>> {
+    let (input, error) = le_u16(input)?;
+
+    Ok((input, BitFlags::from_bits_truncate(error)))
+  }
+
+  /// Stable, machine-readable name of the error.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      ErrorFlags::NoMedia => "no_media",
+      ErrorFlags::EndOfMedia => "end_of_media",
+      ErrorFlags::CutterJam => "cutter_jam",
+      ErrorFlags::WeakBatteries => "weak_batteries",
+      ErrorFlags::PrinterInUse => "printer_in_use",
+      ErrorFlags::PrinterTurnedOff => "printer_turned_off",
+      ErrorFlags::HighVoltageAdapter => "high_voltage_adapter",
+      ErrorFlags::FanMotorError => "fan_motor_error",
+      ErrorFlags::ReplaceMedia => "replace_media",
+      ErrorFlags::ExpansionBufferFull => "expansion_buffer_full",
+      ErrorFlags::CommunicationError => "communication_error",
+      ErrorFlags::CommunicationBufferFull => "communication_buffer_full",
+      ErrorFlags::CoverOpen => "cover_open",
+      ErrorFlags::Overheating => "overheating",
+      ErrorFlags::BlackMarkingNotDetected => "black_marking_not_detected",
+      ErrorFlags::SystemError => "system_error",
+    }
+  }
+}
+
+/// The mode byte of a status reply. The remaining bits are reserved.
+#[bitflags]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModeFlags {
+  AutoCut = (1 << 6),
+  MirrorPrinting = (1 << 7),
+}
+
+impl ModeFlags {
+  pub fn parse(input: &[u8]) -> IResult<&[u8], BitFlags<Self>> {
+    let (input, mode) = nom::number::complete::u8(input)?;
+
+    Ok((input, BitFlags::from_bits_truncate(mode)))
+  }
+
+  /// Stable, machine-readable name of the mode.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      ModeFlags::AutoCut => "auto_cut",
+      ModeFlags::MirrorPrinting => "mirror_printing",
+    }
+  }
+}
@@ -3,8 +3,14 @@ use nom::{IResult, number::complete::u8};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatusType {
+  /// A reply to a status request.
   Ok,
+  PrintingCompleted,
+  /// An error occurred, see [`crate::Status::error`].
   TapeDoorOpen,
+  TurnedOff,
+  Notification,
+  PhaseChange,
   Unknown(u8),
 }
 
@@ -12,7 +18,11 @@ impl From<u8> for StatusType {
   fn from(value: u8) -> Self {
     match value {
       0x00 => StatusType::Ok,
+      0x01 => StatusType::PrintingCompleted,
       0x02 => StatusType::TapeDoorOpen,
+      0x04 => StatusType::TurnedOff,
+      0x05 => StatusType::Notification,
+      0x06 => StatusType::PhaseChange,
       v => StatusType::Unknown(v),
     }
   }
@@ -25,3 +35,18 @@ impl StatusType {
     Ok((input, color.into()))
   }
 }
+
+impl StatusType {
+  /// Stable, machine-readable name of the status.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      StatusType::Ok => "ok",
+      StatusType::PrintingCompleted => "printing_completed",
+      StatusType::TapeDoorOpen => "tape_door_open",
+      StatusType::TurnedOff => "turned_off",
+      StatusType::Notification => "notification",
+      StatusType::PhaseChange => "phase_change",
+      StatusType::Unknown(_) => "unknown",
+    }
+  }
+}
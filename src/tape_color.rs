@@ -81,3 +81,74 @@ impl TapeColor {
     Ok((input, color.into()))
   }
 }
+
+impl TapeColor {
+  /// Approximate sRGB colour of the tape surface, for rendering previews.
+  pub const fn rgb(&self) -> [u8; 3] {
+    match self {
+      TapeColor::BerryPink_TZe_MQP35 => [0xe0, 0x4f, 0x8b],
+      TapeColor::Black => [0x1a, 0x1a, 0x1a],
+      TapeColor::Blue_TZe_5_345_5 => [0x00, 0x4f, 0x9f],
+      TapeColor::Blue => [0x1e, 0x6f, 0xc8],
+      TapeColor::Clear => [0xea, 0xee, 0xf0],
+      TapeColor::ClearMatte => [0xdf, 0xe3, 0xe4],
+      TapeColor::GoldSatin => [0xc9, 0xa4, 0x3c],
+      TapeColor::Green => [0x1b, 0x9a, 0x4b],
+      TapeColor::LightGray_TZe_MQL35 => [0xc4, 0xc7, 0xc9],
+      TapeColor::LimeGreen_TZe_MQG35 => [0xa8, 0xd6, 0x3f],
+      TapeColor::OrangeFluorescent => [0xff, 0x7a, 0x1f],
+      TapeColor::Pink => [0xf6, 0xa8, 0xc8],
+      TapeColor::Red_TZe_435 => [0xc4, 0x12, 0x30],
+      TapeColor::Red => [0xd8, 0x26, 0x2c],
+      TapeColor::SilverMatte => [0xb8, 0xbb, 0xbe],
+      TapeColor::SilverSatin => [0xc9, 0xcc, 0xd1],
+      TapeColor::Yellow | TapeColor::YellowFlexId => [0xff, 0xd9, 0x00],
+      TapeColor::YellowFluorescent => [0xe6, 0xff, 0x1a],
+      TapeColor::WhiteMatte => [0xf7, 0xf7, 0xf4],
+      TapeColor::None
+      | TapeColor::Cleaning
+      | TapeColor::HeatShrinkTube
+      | TapeColor::Incompatible
+      | TapeColor::Stencil
+      | TapeColor::White
+      | TapeColor::WhiteFlexId
+      | TapeColor::Other
+      | TapeColor::Unknown(_) => [0xff, 0xff, 0xff],
+    }
+  }
+
+  /// Stable, machine-readable name of the tape colour.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      TapeColor::None => "none",
+      TapeColor::BerryPink_TZe_MQP35 => "berry_pink_tze_mqp35",
+      TapeColor::Black => "black",
+      TapeColor::Blue_TZe_5_345_5 => "blue_tze_5_345_5",
+      TapeColor::Blue => "blue",
+      TapeColor::Cleaning => "cleaning",
+      TapeColor::Clear => "clear",
+      TapeColor::ClearMatte => "clear_matte",
+      TapeColor::GoldSatin => "gold_satin",
+      TapeColor::Green => "green",
+      TapeColor::HeatShrinkTube => "heat_shrink_tube",
+      TapeColor::Incompatible => "incompatible",
+      TapeColor::LightGray_TZe_MQL35 => "light_gray_tze_mql35",
+      TapeColor::LimeGreen_TZe_MQG35 => "lime_green_tze_mqg35",
+      TapeColor::OrangeFluorescent => "orange_fluorescent",
+      TapeColor::Pink => "pink",
+      TapeColor::Red_TZe_435 => "red_tze_435",
+      TapeColor::Red => "red",
+      TapeColor::SilverMatte => "silver_matte",
+      TapeColor::SilverSatin => "silver_satin",
+      TapeColor::Stencil => "stencil",
+      TapeColor::White => "white",
+      TapeColor::WhiteFlexId => "white_flex_id",
+      TapeColor::WhiteMatte => "white_matte",
+      TapeColor::Yellow => "yellow",
+      TapeColor::YellowFlexId => "yellow_flex_id",
+      TapeColor::YellowFluorescent => "yellow_fluorescent",
+      TapeColor::Other => "other",
+      TapeColor::Unknown(_) => "unknown",
+    }
+  }
+}
@@ -1,19 +1,19 @@
-use nom::{
-  IResult,
-  error::{ErrorKind, FromExternalError},
-  number::complete::u8,
-};
+use nom::{IResult, number::complete::u8};
 
-use crate::Error;
+use crate::MediaType;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TapeInfo {
+  /// Width of the printable area, in dots.
   pub px: u32,
   pub margins: f32,
+  /// The first dot of the print head that lies over the printable area.
+  pub offset: u32,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TapeSize {
   None,
@@ -24,45 +24,233 @@ pub enum TapeSize {
   Eighteen,
   TwentyFour,
   ThirtySix,
+  /// HSe 2:1 heat shrink tube, named by its flattened width in mm.
+  HeatShrink5_8,
+  HeatShrink8_8,
+  HeatShrink11_7,
+  HeatShrink17_7,
+  HeatShrink23_6,
+  /// HSe 3:1 heat shrink tube, named by its flattened width in mm.
+  HeatShrink5_2,
+  HeatShrink9_0,
+  HeatShrink11_2,
+  HeatShrink21_0,
+  HeatShrink31_0,
+  /// FLe flag labels, 21 mm wide, on 24 mm backing.
+  Fle21,
+  /// DK continuous length tape for QL printers, by width in mm.
+  Continuous(u8),
+  /// DK die-cut labels for QL printers, by width in mm. The length of each
+  /// label is in [`crate::Status::media_length`].
+  DieCut(u8),
+  /// A width this crate doesn't know the geometry of. The printer's status
+  /// can still be read, but printing on it is refused.
+  Unknown(u8),
 }
 
 impl TapeSize {
+  /// Geometry on a 180 dpi, 128 dot print head, which most printers have.
+  /// See [`crate::PrinterType::tape_info`] for the geometry on a particular
+  /// printer.
   pub const fn info(&self) -> TapeInfo {
     match self {
       TapeSize::None => TapeInfo {
         px: 0,
-        margins: 0.0
+        margins: 0.0,
+        offset: 64,
       },
       TapeSize::ThreePointFive => TapeInfo {
         px: 24,
         margins: 0.5,
+        offset: 52,
       },
       TapeSize::Six => TapeInfo {
         px: 32,
         margins: 1.0,
+        offset: 48,
       },
       TapeSize::Nine => TapeInfo {
         px: 52,
         margins: 1.0,
+        offset: 38,
       },
       TapeSize::Twelve => TapeInfo {
         px: 76,
         margins: 2.0,
+        offset: 26,
       },
       TapeSize::Eighteen => TapeInfo {
         px: 120,
         margins: 3.0,
+        offset: 4,
       },
       TapeSize::TwentyFour => TapeInfo {
         px: 128,
         margins: 3.0,
+        offset: 0,
       },
       TapeSize::ThirtySix => TapeInfo {
         px: 192,
         margins: 4.5,
+        offset: 0,
+      },
+      // The seam and curvature of a tube leave less of it printable than
+      // tape of the same width.
+      TapeSize::HeatShrink5_8 => TapeInfo {
+        px: 28,
+        margins: 1.0,
+        offset: 50,
+      },
+      TapeSize::HeatShrink8_8 => TapeInfo {
+        px: 48,
+        margins: 1.0,
+        offset: 40,
+      },
+      TapeSize::HeatShrink11_7 => TapeInfo {
+        px: 66,
+        margins: 2.0,
+        offset: 31,
+      },
+      TapeSize::HeatShrink17_7 => TapeInfo {
+        px: 106,
+        margins: 3.0,
+        offset: 11,
+      },
+      TapeSize::HeatShrink23_6 => TapeInfo {
+        px: 128,
+        margins: 3.0,
+        offset: 0,
+      },
+      TapeSize::HeatShrink5_2 => TapeInfo {
+        px: 22,
+        margins: 1.0,
+        offset: 53,
+      },
+      TapeSize::HeatShrink9_0 => TapeInfo {
+        px: 48,
+        margins: 1.0,
+        offset: 40,
+      },
+      TapeSize::HeatShrink11_2 => TapeInfo {
+        px: 62,
+        margins: 2.0,
+        offset: 33,
+      },
+      TapeSize::HeatShrink21_0 => TapeInfo {
+        px: 112,
+        margins: 3.0,
+        offset: 8,
+      },
+      TapeSize::HeatShrink31_0 => TapeInfo {
+        px: 128,
+        margins: 4.5,
+        offset: 0,
+      },
+      TapeSize::Fle21 => TapeInfo {
+        px: 112,
+        margins: 3.0,
+        offset: 8,
+      },
+      // DK media only fits QL printers, see `TapeSize::ql_info`.
+      TapeSize::Continuous(_) | TapeSize::DieCut(_) | TapeSize::Unknown(_) => TapeInfo {
+        px: 0,
+        margins: 0.0,
+        offset: 64,
       },
     }
   }
+
+  /// Geometry of DK media on the 300 dpi, 720 dot head of QL printers.
+  pub const fn ql_info(&self) -> Option<TapeInfo> {
+    let (px, margins, offset) = match self {
+      TapeSize::Continuous(12) => (106, 1.5, 585),
+      TapeSize::Continuous(29) => (306, 1.5, 408),
+      TapeSize::Continuous(38) => (413, 1.5, 295),
+      TapeSize::Continuous(50) => (554, 1.5, 154),
+      TapeSize::Continuous(54) => (590, 1.9, 130),
+      TapeSize::Continuous(62) => (696, 1.5, 12),
+      TapeSize::DieCut(17) => (165, 1.5, 555),
+      TapeSize::DieCut(23) => (202, 3.0, 476),
+      TapeSize::DieCut(29) => (306, 1.5, 408),
+      TapeSize::DieCut(39) => (425, 1.5, 289),
+      TapeSize::DieCut(52) => (578, 1.5, 142),
+      TapeSize::DieCut(62) => (696, 1.5, 12),
+      // Round labels, by diameter.
+      TapeSize::DieCut(12) => (94, 2.0, 513),
+      TapeSize::DieCut(24) => (236, 2.0, 442),
+      TapeSize::DieCut(58) => (618, 3.0, 51),
+      _ => return None,
+    };
+
+    Some(TapeInfo {
+      px,
+      margins,
+      offset,
+    })
+  }
+
+  pub const fn mm(&self) -> f32 {
+    match self {
+      TapeSize::None => 0.0,
+      TapeSize::ThreePointFive => 3.5,
+      TapeSize::Six => 6.0,
+      TapeSize::Nine => 9.0,
+      TapeSize::Twelve => 12.0,
+      TapeSize::Eighteen => 18.0,
+      TapeSize::TwentyFour => 24.0,
+      TapeSize::ThirtySix => 36.0,
+      TapeSize::HeatShrink5_8 => 5.8,
+      TapeSize::HeatShrink8_8 => 8.8,
+      TapeSize::HeatShrink11_7 => 11.7,
+      TapeSize::HeatShrink17_7 => 17.7,
+      TapeSize::HeatShrink23_6 => 23.6,
+      TapeSize::HeatShrink5_2 => 5.2,
+      TapeSize::HeatShrink9_0 => 9.0,
+      TapeSize::HeatShrink11_2 => 11.2,
+      TapeSize::HeatShrink21_0 => 21.0,
+      TapeSize::HeatShrink31_0 => 31.0,
+      TapeSize::Fle21 => 21.0,
+      TapeSize::Continuous(width) | TapeSize::DieCut(width) | TapeSize::Unknown(width) => {
+        *width as f32
+      },
+    }
+  }
+
+  /// Looks up the width reported in a status reply, which depends on the
+  /// type of media: heat shrink tubes, FLe labels and DK media have their own
+  /// codes.
+  pub fn from_media(width: u8, media_type: MediaType) -> Self {
+    let size = match (media_type, width) {
+      (MediaType::Continuous, width) => Self::Continuous(width),
+      (MediaType::DieCut, width) => Self::DieCut(width),
+      (_, width) => return Self::from_tape(width, media_type),
+    };
+
+    match size.ql_info() {
+      Some(_) => size,
+      None => Self::Unknown(width),
+    }
+  }
+
+  fn from_tape(width: u8, media_type: MediaType) -> Self {
+    match (media_type, width) {
+      (MediaType::HeatShrink, 6) => Self::HeatShrink5_8,
+      (MediaType::HeatShrink, 9) => Self::HeatShrink8_8,
+      (MediaType::HeatShrink, 12) => Self::HeatShrink11_7,
+      (MediaType::HeatShrink, 18) => Self::HeatShrink17_7,
+      (MediaType::HeatShrink, 24) => Self::HeatShrink23_6,
+      (MediaType::HeatShrink3To1, 5) => Self::HeatShrink5_2,
+      (MediaType::HeatShrink3To1, 9) => Self::HeatShrink9_0,
+      (MediaType::HeatShrink3To1, 11) => Self::HeatShrink11_2,
+      (MediaType::HeatShrink3To1, 21) => Self::HeatShrink21_0,
+      (MediaType::HeatShrink3To1, 31) => Self::HeatShrink31_0,
+      (MediaType::Fle, 21) => Self::Fle21,
+      (MediaType::HeatShrink | MediaType::HeatShrink3To1 | MediaType::Fle, width) => {
+        Self::Unknown(width)
+      },
+      (_, width) => Self::from(width),
+    }
+  }
 }
 
 impl From<TapeSize> for u8 {
@@ -76,15 +264,27 @@ impl From<TapeSize> for u8 {
       TapeSize::Eighteen => 18,
       TapeSize::TwentyFour => 24,
       TapeSize::ThirtySix => 36,
+      TapeSize::HeatShrink5_8 => 6,
+      TapeSize::HeatShrink8_8 => 9,
+      TapeSize::HeatShrink11_7 => 12,
+      TapeSize::HeatShrink17_7 => 18,
+      TapeSize::HeatShrink23_6 => 24,
+      TapeSize::HeatShrink5_2 => 5,
+      TapeSize::HeatShrink9_0 => 9,
+      TapeSize::HeatShrink11_2 => 11,
+      TapeSize::HeatShrink21_0 => 21,
+      TapeSize::HeatShrink31_0 => 31,
+      TapeSize::Fle21 => 21,
+      TapeSize::Continuous(width) | TapeSize::DieCut(width) | TapeSize::Unknown(width) => width,
     }
   }
 }
 
-impl TryFrom<u8> for TapeSize {
-  type Error = Error;
-
-  fn try_from(value: u8) -> Result<Self, Self::Error> {
-    Ok(match value {
+/// Only covers widths of regular tape, see [`TapeSize::from_media`] for
+/// other media.
+impl From<u8> for TapeSize {
+  fn from(value: u8) -> Self {
+    match value {
       0 => Self::None,
       4 => Self::ThreePointFive,
       6 => Self::Six,
@@ -93,22 +293,15 @@ impl TryFrom<u8> for TapeSize {
       18 => Self::Eighteen,
       24 => Self::TwentyFour,
       36 => Self::ThirtySix,
-      other => Err(Error::InvalidTapeSize(other))?,
-    })
+      other => Self::Unknown(other),
+    }
   }
 }
 
 impl TapeSize {
   pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
     let (input, size) = u8(input)?;
-    let size = match Self::try_from(size) {
-      Ok(size) => size,
-      Err(e) => {
-        let err = nom::error::Error::from_external_error(input, ErrorKind::Fail, e);
-        Err(nom::Err::Error(err))?
-      },
-    };
 
-    Ok((input, size))
+    Ok((input, size.into()))
   }
 }
@@ -0,0 +1,171 @@
+use std::{
+  collections::BTreeMap,
+  fs::File,
+  io,
+  path::{Path, PathBuf},
+};
+
+use image::DynamicImage;
+
+use crate::{Error, PrintOptions, PrinterFlags, PrinterType, Quirks};
+
+/// Tape consumed by a print job.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TapeUsage {
+  pub labels: u32,
+  /// Length of all labels, in millimetres.
+  pub printed: f32,
+  /// Blank tape fed out at cuts and to bring the last label to the cutter,
+  /// in millimetres.
+  pub waste: f32,
+}
+
+impl TapeUsage {
+  /// Estimates the tape used by printing every image in `images` with
+  /// `options`, each with its own call to `print_with`.
+  pub fn estimate(
+    ty: PrinterType,
+    images: &[DynamicImage],
+    options: &PrintOptions,
+  ) -> Result<Self, Error> {
+    Self::estimate_with(ty, ty.info().quirks, images, options)
+  }
+
+  /// Like [`TapeUsage::estimate`], for a printer opened with its quirks
+  /// overridden.
+  pub fn estimate_with(
+    ty: PrinterType,
+    quirks: Quirks,
+    images: &[DynamicImage],
+    options: &PrintOptions,
+  ) -> Result<Self, Error> {
+    let info = ty.info();
+    let auto_cut = options.precut && info.flags.contains(PrinterFlags::HasPrecut);
+
+    let mut usage = TapeUsage::default();
+
+    for image in images {
+      let lines = ty.label_lines(image.width(), options)? + quirks.padding;
+
+      usage.labels += options.copies;
+      usage.printed += info.mm(lines) * options.copies as f32;
+
+      // Copies are cut apart only when the printer cuts every label, and
+      // the last one is only fed out to the cutter when it isn't chained.
+      let mut cuts = if auto_cut {
+        options.copies.saturating_sub(1)
+      } else {
+        0
+      };
+
+      if !options.chain {
+        cuts += 1;
+        usage.waste += info.leader;
+      }
+
+      usage.waste += info.cut_margin * cuts as f32;
+    }
+
+    Ok(usage)
+  }
+
+  /// Total length of tape consumed, in millimetres.
+  pub fn total(&self) -> f32 {
+    self.printed + self.waste
+  }
+}
+
+/// Running totals of tape used per printer, kept in a file keyed by serial
+/// number.
+#[derive(Debug, Clone)]
+pub struct TapeCounter {
+  path: PathBuf,
+  totals: BTreeMap<String, f32>,
+}
+
+impl TapeCounter {
+  /// Loads the counter stored at `path`, starting empty if it doesn't
+  /// exist yet.
+  pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+    let path = path.into();
+    let totals = Self::read(&path)?;
+
+    Ok(TapeCounter { path, totals })
+  }
+
+  fn read(path: &Path) -> Result<BTreeMap<String, f32>, Error> {
+    let contents = match std::fs::read_to_string(path) {
+      Ok(contents) => contents,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+      Err(e) => Err(Error::Io(e))?,
+    };
+
+    let mut totals = BTreeMap::new();
+
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+      let parsed = line
+        .rsplit_once('\t')
+        .and_then(|(serial, mm)| Some((serial, mm.parse::<f32>().ok()?)));
+
+      let Some((serial, mm)) = parsed else {
+        Err(Error::Io(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("invalid tape counter line: {line:?}"),
+        )))?
+      };
+
+      totals.insert(serial.to_string(), mm);
+    }
+
+    Ok(totals)
+  }
+
+  /// Millimetres of tape used by the printer with this serial number.
+  pub fn get(&self, serial: &str) -> f32 {
+    self.totals.get(serial).copied().unwrap_or(0.0)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+    self
+      .totals
+      .iter()
+      .map(|(serial, mm)| (serial.as_str(), *mm))
+  }
+
+  /// Adds `usage` to the total for `serial` and writes the counter back to
+  /// its file.
+  ///
+  /// The file is locked while it's updated, through a `.lock` file next to
+  /// it, and totals recorded by other processes since it was opened are
+  /// kept. The new totals are written to a temporary file that replaces the
+  /// old one, so the counter is never left half-written.
+  pub fn record(&mut self, serial: &str, usage: &TapeUsage) -> Result<(), Error> {
+    let lock = File::create(Self::sibling(&self.path, "lock")).map_err(Error::Io)?;
+    lock.lock().map_err(Error::Io)?;
+
+    self.totals = Self::read(&self.path)?;
+    *self.totals.entry(serial.to_string()).or_default() += usage.total();
+
+    let contents = self
+      .totals
+      .iter()
+      .map(|(serial, mm)| format!("{serial}\t{mm}\n"))
+      .collect::<String>();
+
+    let temp = Self::sibling(&self.path, "tmp");
+    std::fs::write(&temp, contents).map_err(Error::Io)?;
+    std::fs::rename(&temp, &self.path).map_err(Error::Io)?;
+
+    Ok(())
+  }
+
+  /// `path` with `extension` added on the end.
+  fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+
+    path.into()
+  }
+}
@@ -43,3 +43,41 @@ impl TextColor {
     Ok((input, color.into()))
   }
 }
+
+impl TextColor {
+  /// Approximate sRGB colour of the ink, for rendering previews.
+  pub const fn rgb(&self) -> [u8; 3] {
+    match self {
+      TextColor::Blue => [0x0b, 0x3d, 0x91],
+      TextColor::BlueF => [0x1f, 0x5f, 0xd1],
+      TextColor::Gold => [0xc0, 0x9a, 0x2e],
+      TextColor::Red => [0xc8, 0x10, 0x1e],
+      TextColor::White => [0xff, 0xff, 0xff],
+      TextColor::None
+      | TextColor::Black
+      | TextColor::Cleaning
+      | TextColor::Incompatible
+      | TextColor::Stencil
+      | TextColor::Other
+      | TextColor::Unknown(_) => [0x00, 0x00, 0x00],
+    }
+  }
+
+  /// Stable, machine-readable name of the ink colour.
+  pub const fn name(&self) -> &'static str {
+    match self {
+      TextColor::None => "none",
+      TextColor::Black => "black",
+      TextColor::Blue => "blue",
+      TextColor::BlueF => "blue_f",
+      TextColor::Cleaning => "cleaning",
+      TextColor::Gold => "gold",
+      TextColor::Incompatible => "incompatible",
+      TextColor::Red => "red",
+      TextColor::Stencil => "stencil",
+      TextColor::White => "white",
+      TextColor::Other => "other",
+      TextColor::Unknown(_) => "unknown",
+    }
+  }
+}
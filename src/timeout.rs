@@ -0,0 +1,58 @@
+use std::{future::Future, time::Duration};
+
+use futures_timer::Delay;
+
+/// Races `future` against a timer, returning `None` if the timer wins.
+///
+/// The timer runs on its own thread rather than an executor's reactor, so
+/// this works the same under any runtime, or none at all. The losing
+/// future is dropped, which for nusb transfers cancels them.
+pub(crate) async fn timeout<T>(duration: Duration, future: impl Future<Output = T>) -> Option<T> {
+  futures_lite::future::or(async { Some(future.await) }, async {
+    Delay::new(duration).await;
+    None
+  })
+  .await
+}
+
+/// The async API mustn't assume a particular runtime: if the timer reached
+/// for a tokio reactor, these would panic instead of timing out.
+#[cfg(test)]
+mod tests {
+  use std::future::pending;
+
+  use super::*;
+  use crate::{Error, Operation, PrinterInterface};
+
+  const LIMIT: Duration = Duration::from_millis(20);
+
+  async fn never_completes() {
+    assert_eq!(timeout(LIMIT, pending::<()>()).await, None);
+    assert_eq!(timeout(LIMIT, async { 7 }).await, Some(7));
+
+    let result =
+      PrinterInterface::with_timeout(Some(LIMIT), Operation::Receive, pending::<()>()).await;
+
+    assert!(matches!(
+      result,
+      Err(Error::Timeout {
+        operation: Operation::Receive
+      })
+    ));
+  }
+
+  #[test]
+  fn smol() {
+    smol::block_on(never_completes());
+  }
+
+  #[test]
+  fn async_std() {
+    async_std::task::block_on(never_completes());
+  }
+
+  #[test]
+  fn block_on() {
+    futures_lite::future::block_on(never_completes());
+  }
+}
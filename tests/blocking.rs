@@ -0,0 +1,66 @@
+//! The blocking wrapper, driven through the same sink as the async tests.
+
+use std::{
+  io::Write,
+  sync::{Arc, Mutex},
+};
+
+use ptouch_rs::{PrinterType, TapeSize, blocking::Printer};
+
+/// A writer whose contents can still be read after it's handed over.
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0.lock().unwrap().write(buf)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+fn image() -> image::DynamicImage {
+  image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 76))
+}
+
+#[test]
+fn print_to_writer() {
+  let output = Shared::default();
+
+  let printer = Printer::write_to(output.clone(), PrinterType::PT_P700, TapeSize::Twelve).unwrap();
+  assert_eq!(printer.ty(), PrinterType::PT_P700);
+  assert_eq!(printer.status().media_width, TapeSize::Twelve);
+
+  printer.print(image()).unwrap();
+
+  // The reset, then the job ending in a print and feed.
+  let written = output.0.lock().unwrap();
+  assert_eq!(&written[100..102], b"\x1b@");
+  assert_eq!(written.last(), Some(&0x1a));
+}
+
+#[test]
+fn print_to_file() {
+  let path = std::env::temp_dir().join(format!("ptouch-blocking-{}", std::process::id()));
+
+  let printer = Printer::create(&path, PrinterType::PT_P700, TapeSize::Twelve).unwrap();
+  printer.print(image()).unwrap();
+  drop(printer);
+
+  // The same job the async printer writes.
+  let output = Shared::default();
+  let expected = smol::block_on(ptouch_rs::Printer::write_to(
+    output.clone(),
+    PrinterType::PT_P700,
+    TapeSize::Twelve,
+  ))
+  .unwrap();
+  smol::block_on(expected.print(image())).unwrap();
+
+  let written = std::fs::read(&path).unwrap();
+  let _ = std::fs::remove_file(&path);
+
+  assert_eq!(written, *output.0.lock().unwrap());
+}
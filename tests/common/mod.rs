@@ -0,0 +1,59 @@
+//! A printer at the other end of a byte stream, for the transport tests.
+
+use std::io::{Read, Write};
+
+pub const STATUS_REQUEST: &[u8] = b"\x1biS";
+
+/// Answers each status request on `stream` by calling `answer` with its
+/// index, which may reply late, in pieces, or not at all. Returns
+/// everything it was sent once the other end goes away.
+pub fn serve<S: Read + Write>(mut stream: S, mut answer: impl FnMut(&mut S, usize)) -> Vec<u8> {
+  let mut received = Vec::new();
+  let mut answered = 0;
+  let mut buf = [0; 1024];
+
+  loop {
+    // A closed socket reads nothing, while a pty fails to read once nothing
+    // has the other side open any more.
+    let n = match stream.read(&mut buf) {
+      Ok(0) | Err(_) => return received,
+      Ok(n) => n,
+    };
+
+    received.extend_from_slice(&buf[..n]);
+
+    let requests = received
+      .windows(STATUS_REQUEST.len())
+      .filter(|window| *window == STATUS_REQUEST)
+      .count();
+
+    for request in answered..requests {
+      answer(&mut stream, request);
+    }
+
+    answered = requests;
+  }
+}
+
+/// Checks `received` starts the way opening a printer does, with an
+/// invalidate, initialize and status request, and returns what followed.
+pub fn opened(received: &[u8]) -> &[u8] {
+  assert!(received[..400].iter().all(|byte| *byte == 0));
+  assert_eq!(&received[400..402], b"\x1b@");
+  assert_eq!(&received[402..405], STATUS_REQUEST);
+
+  &received[405..]
+}
+
+/// Checks `job` is `header`, raster lines for a 128 dot head and a print
+/// and feed, and returns the lines.
+pub fn raster_lines<'a>(job: &'a [u8], header: &[u8]) -> Vec<&'a [u8]> {
+  assert!(job.starts_with(header), "{job:x?}");
+  assert_eq!(job.last(), Some(&0x1a));
+
+  // Every line as a single packbits run over the 16 byte head.
+  job[header.len()..job.len() - 1]
+    .chunks(20)
+    .inspect(|line| assert_eq!(&line[..4], [b'G', 17, 0, 15]))
+    .collect()
+}
@@ -0,0 +1,55 @@
+//! Where each tape lies under the print head: the first pin over the
+//! printable area and how many dots it's wide.
+
+use ptouch_rs::{PrinterType, TapeSize};
+
+fn pins(ty: PrinterType, size: TapeSize) -> (u32, u32) {
+  let tape = ty.tape_info(size);
+
+  (tape.offset, tape.px)
+}
+
+#[test]
+fn pt_9200dx() {
+  let ty = PrinterType::PT_9200DX;
+
+  assert_eq!(pins(ty, TapeSize::ThreePointFive), (168, 48));
+  assert_eq!(pins(ty, TapeSize::Twelve), (117, 150));
+  assert_eq!(pins(ty, TapeSize::TwentyFour), (32, 320));
+  assert_eq!(pins(ty, TapeSize::ThirtySix), (0, 384));
+
+  // 150 dots at 360 dpi leave 0.71 mm either side of 12 mm tape, not the
+  // 2 mm that 76 dots do at 180 dpi.
+  let margins = ty.tape_info(TapeSize::Twelve).margins;
+  assert!((margins - 0.708).abs() < 0.01, "{margins}");
+}
+
+#[test]
+fn pt_p700() {
+  let ty = PrinterType::PT_P700;
+
+  assert_eq!(pins(ty, TapeSize::ThreePointFive), (52, 24));
+  assert_eq!(pins(ty, TapeSize::Twelve), (26, 76));
+  assert_eq!(pins(ty, TapeSize::Eighteen), (4, 120));
+  assert_eq!(pins(ty, TapeSize::TwentyFour), (0, 128));
+  assert_eq!(pins(ty, TapeSize::HeatShrink11_7), (31, 66));
+  assert_eq!(ty.tape_info(TapeSize::Twelve).margins, 2.0);
+}
+
+#[test]
+fn pt_p900() {
+  let ty = PrinterType::PT_P900;
+
+  assert_eq!(pins(ty, TapeSize::ThreePointFive), (248, 48));
+  assert_eq!(pins(ty, TapeSize::Twelve), (197, 150));
+  assert_eq!(pins(ty, TapeSize::ThirtySix), (61, 454));
+
+  // Heat shrink tube and FLe labels have their own entries rather than
+  // being centred under the head.
+  assert_eq!(pins(ty, TapeSize::HeatShrink11_7), (206, 132));
+  assert_eq!(pins(ty, TapeSize::HeatShrink21_0), (160, 224));
+  assert_eq!(pins(ty, TapeSize::Fle21), (160, 224));
+
+  // The rest of the series shares the head.
+  assert_eq!(pins(PrinterType::PT_P950NW, TapeSize::Twelve), (197, 150));
+}
@@ -0,0 +1,344 @@
+//! A TCP listener standing in for a network printer on port 9100.
+
+mod common;
+
+use std::{
+  io::Write,
+  net::{TcpListener, TcpStream},
+  sync::Mutex,
+  thread::JoinHandle,
+  time::{Duration, Instant},
+};
+
+use ptouch_rs::{Error, OpenOptions, Printer, PrinterType, RetryPolicy, TapeSize, Timeouts};
+
+/// PT-P750W, idle, with 12 mm black-on-white laminated tape loaded.
+const P750W_IDLE: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x30, 0x68, 0x30, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x01, 0x00, 0x00, 0x00, 0x40,
+  0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Accepts a single connection and plays a printer on it until it's
+/// closed.
+fn fake_printer() -> (String, JoinHandle<Vec<u8>>) {
+  fake_printer_with(|stream, _| stream.write_all(&P750W_IDLE).unwrap())
+}
+
+/// Like [`fake_printer`], answering status requests with `answer`.
+fn fake_printer_with(
+  answer: impl FnMut(&mut TcpStream, usize) + Send + 'static,
+) -> (String, JoinHandle<Vec<u8>>) {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap().to_string();
+
+  let handle = std::thread::spawn(move || {
+    let (stream, _) = listener.accept().unwrap();
+
+    common::serve(stream, answer)
+  });
+
+  (addr, handle)
+}
+
+/// Waits `receive` for each status reply, and retries as `status_retry`
+/// says.
+fn options(receive: Duration, status_retry: RetryPolicy) -> OpenOptions {
+  OpenOptions {
+    timeouts: Timeouts {
+      receive: Some(receive),
+      ..Default::default()
+    },
+    status_retry,
+    ..Default::default()
+  }
+}
+
+/// Retries `attempts` times in all, `delay` apart.
+fn retry(attempts: u32, delay: Duration) -> RetryPolicy {
+  RetryPolicy {
+    attempts,
+    delay,
+    backoff: 1.0,
+    deadline: None,
+  }
+}
+
+/// A printer that doesn't answer the first `ignored` status requests.
+fn slow_printer(ignored: usize) -> (String, JoinHandle<Vec<u8>>) {
+  fake_printer_with(move |stream, request| {
+    if request >= ignored {
+      stream.write_all(&P750W_IDLE).unwrap();
+    }
+  })
+}
+
+fn status_requests(received: &[u8]) -> usize {
+  received
+    .windows(common::STATUS_REQUEST.len())
+    .filter(|window| *window == common::STATUS_REQUEST)
+    .count()
+}
+
+#[test]
+fn connect_identifies_printer_from_status() {
+  let (addr, handle) = fake_printer();
+
+  let printer = smol::block_on(Printer::connect(addr)).unwrap();
+
+  assert_eq!(printer.ty(), PrinterType::PT_P750W);
+  assert_eq!(printer.status().media_width, TapeSize::Twelve);
+  assert_eq!(printer.serial_number(), None);
+
+  drop(printer);
+
+  let received = handle.join().unwrap();
+
+  // Nothing beyond the invalidate, initialize and status request.
+  assert_eq!(common::opened(&received), b"");
+}
+
+#[test]
+fn print_sends_raster_stream() {
+  let (addr, handle) = fake_printer();
+
+  let printer = smol::block_on(Printer::connect(addr)).unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 76));
+  smol::block_on(printer.print(image)).unwrap();
+
+  drop(printer);
+
+  let received = handle.join().unwrap();
+
+  // Compression, raster mode, then advanced mode telling the P750W to feed
+  // and cut after the label.
+  let header = b"M\x02\x1bia\x01M\x02\x1biK\x08";
+  let lines = common::raster_lines(common::opened(&received), header);
+  assert_eq!(lines.len(), 40);
+}
+
+#[test]
+fn reply_in_fragments() {
+  let (addr, handle) = fake_printer_with(|stream, _| {
+    // The tail of some earlier reply, then the reply itself a few bytes at
+    // a time.
+    stream.write_all(&[0x00, 0x14, 0x00]).unwrap();
+
+    for piece in P750W_IDLE.chunks(7) {
+      std::thread::sleep(Duration::from_millis(10));
+      stream.write_all(piece).unwrap();
+    }
+  });
+
+  let printer = smol::block_on(Printer::connect(addr)).unwrap();
+
+  assert_eq!(printer.ty(), PrinterType::PT_P750W);
+  assert_eq!(printer.status().media_width, TapeSize::Twelve);
+
+  drop(printer);
+  handle.join().unwrap();
+}
+
+#[test]
+fn late_reply_discarded() {
+  let mut p750w_18mm = P750W_IDLE;
+  p750w_18mm[10] = 18;
+
+  let (addr, handle) = fake_printer_with(move |stream, request| {
+    if request == 0 {
+      // Too late for the first request, but well before the retry, with
+      // what the printer looked like then.
+      std::thread::sleep(Duration::from_millis(200));
+      stream.write_all(&p750w_18mm).unwrap();
+    } else {
+      stream.write_all(&P750W_IDLE).unwrap();
+    }
+  });
+
+  let options = options(
+    Duration::from_millis(100),
+    retry(3, Duration::from_millis(400)),
+  );
+  let mut printer = smol::block_on(Printer::connect_with(addr, options)).unwrap();
+
+  // The late reply to the first request isn't taken for the answer to the
+  // retry, or to any request after it.
+  assert_eq!(printer.status().media_width, TapeSize::Twelve);
+  let status = smol::block_on(printer.reload_status()).unwrap();
+  assert_eq!(status.media_width, TapeSize::Twelve);
+
+  drop(printer);
+
+  assert_eq!(status_requests(&handle.join().unwrap()), 3);
+}
+
+#[test]
+fn retries_until_answered() {
+  let (addr, handle) = slow_printer(2);
+
+  let options = options(
+    Duration::from_millis(50),
+    retry(3, Duration::from_millis(10)),
+  );
+  let printer = smol::block_on(Printer::connect_with(addr, options)).unwrap();
+
+  assert_eq!(printer.ty(), PrinterType::PT_P750W);
+
+  drop(printer);
+  assert_eq!(status_requests(&handle.join().unwrap()), 3);
+}
+
+#[test]
+fn no_status_reply() {
+  let (addr, handle) = slow_printer(usize::MAX);
+
+  let options = options(
+    Duration::from_millis(50),
+    retry(3, Duration::from_millis(10)),
+  );
+
+  assert!(matches!(
+    smol::block_on(Printer::connect_with(addr, options)),
+    Err(Error::NoStatusReply { attempts: 3 })
+  ));
+  assert_eq!(status_requests(&handle.join().unwrap()), 3);
+}
+
+#[test]
+fn retries_back_off() {
+  let (addr, handle) = slow_printer(usize::MAX);
+
+  let options = options(
+    Duration::from_millis(20),
+    RetryPolicy {
+      backoff: 2.0,
+      ..retry(4, Duration::from_millis(50))
+    },
+  );
+  let started = Instant::now();
+
+  assert!(matches!(
+    smol::block_on(Printer::connect_with(addr, options)),
+    Err(Error::NoStatusReply { attempts: 4 })
+  ));
+
+  // Four receive timeouts, then waits of 50, 100 and 200 ms between them.
+  let elapsed = started.elapsed();
+  assert!(
+    elapsed >= Duration::from_millis(4 * 20 + 350),
+    "{elapsed:?}"
+  );
+  assert_eq!(status_requests(&handle.join().unwrap()), 4);
+}
+
+#[test]
+fn deadline_cuts_receive_short() {
+  let (addr, handle) = slow_printer(usize::MAX);
+
+  // The deadline comes long before the first receive would time out.
+  let options = options(
+    Duration::from_secs(10),
+    RetryPolicy {
+      deadline: Some(Duration::from_millis(200)),
+      ..retry(10, Duration::from_millis(10))
+    },
+  );
+  let started = Instant::now();
+
+  assert!(matches!(
+    smol::block_on(Printer::connect_with(addr, options)),
+    Err(Error::NoStatusReply { attempts: 1 })
+  ));
+
+  let elapsed = started.elapsed();
+  assert!(elapsed < Duration::from_secs(2), "{elapsed:?}");
+  assert_eq!(status_requests(&handle.join().unwrap()), 1);
+}
+
+/// Keeps every warning logged, so tests can check for theirs.
+struct Warnings(Mutex<Vec<String>>);
+
+impl log::Log for Warnings {
+  fn enabled(&self, metadata: &log::Metadata) -> bool {
+    metadata.level() <= log::Level::Warn
+  }
+
+  fn log(&self, record: &log::Record) {
+    if self.enabled(record.metadata()) {
+      self.0.lock().unwrap().push(record.args().to_string());
+    }
+  }
+
+  fn flush(&self) {}
+}
+
+static WARNINGS: Warnings = Warnings(Mutex::new(Vec::new()));
+
+fn expecting(model: PrinterType, strict_model_check: bool) -> OpenOptions {
+  OpenOptions {
+    model: Some(model),
+    strict_model_check,
+    ..Default::default()
+  }
+}
+
+#[test]
+fn model_mismatch_warns() {
+  let _ = log::set_logger(&WARNINGS);
+  log::set_max_level(log::LevelFilter::Warn);
+
+  let (addr, handle) = fake_printer();
+
+  // The P750W answers where a P700 was expected, which is still printed to
+  // as a P700.
+  let printer = smol::block_on(Printer::connect_with(
+    addr,
+    expecting(PrinterType::PT_P700, false),
+  ))
+  .unwrap();
+  assert_eq!(printer.ty(), PrinterType::PT_P700);
+
+  let warnings = WARNINGS.0.lock().unwrap();
+  assert!(
+    warnings
+      .iter()
+      .any(|warning| warning.starts_with("PT_P700 reports model code 0x68")),
+    "{warnings:?}"
+  );
+
+  drop(printer);
+  handle.join().unwrap();
+}
+
+#[test]
+fn model_mismatch_strict() {
+  let (addr, handle) = fake_printer();
+
+  assert!(matches!(
+    smol::block_on(Printer::connect_with(
+      addr,
+      expecting(PrinterType::PT_P700, true)
+    )),
+    Err(Error::ModelMismatch {
+      expected: PrinterType::PT_P700,
+      model_code: 0x68,
+    })
+  ));
+
+  handle.join().unwrap();
+}
+
+#[test]
+fn expected_model_matches() {
+  let (addr, handle) = fake_printer();
+
+  let printer = smol::block_on(Printer::connect_with(
+    addr,
+    expecting(PrinterType::PT_P750W, true),
+  ))
+  .unwrap();
+  assert_eq!(printer.ty(), PrinterType::PT_P750W);
+
+  drop(printer);
+  handle.join().unwrap();
+}
@@ -0,0 +1,150 @@
+//! Printers registered at runtime, and the TOML they can be loaded from.
+
+use ptouch_rs::{DefinitionError, PrinterDefinition, PrinterFlags, PrinterType, TapeSize};
+
+const MODELS: &str = r#"
+[[printer]]
+name = "PT-REGISTRY"
+vendor_id = 0x04f9
+product_id = 0x2f00
+model_code = 0xf0
+max_px = 256
+dpi = 360
+flags = ["RasterPackBits", "HasPrecut"]
+quirks = { padding = 4 }
+tapes = [
+  { tape = "Twelve", px = 150, offset = 53, margins = 0.7 },
+  { tape = "HeatShrink11_7", px = 132, offset = 62 },
+  { tape = { Continuous = 62 }, px = 256, offset = 0 },
+]
+"#;
+
+#[test]
+fn toml_round_trip() {
+  let definitions = PrinterDefinition::from_toml(MODELS).unwrap();
+
+  assert_eq!(definitions.len(), 1);
+  assert_eq!(definitions[0].tapes[1].tape, TapeSize::HeatShrink11_7);
+  assert_eq!(definitions[0].tapes[2].tape, TapeSize::Continuous(62));
+  // Left out, so the same as a built-in P-touch.
+  assert_eq!(definitions[0].min_length, 4.4);
+
+  let toml = PrinterDefinition::to_toml(&definitions).unwrap();
+
+  assert_eq!(PrinterDefinition::from_toml(&toml).unwrap(), definitions);
+}
+
+#[test]
+fn register_and_resolve() {
+  let mut definitions = PrinterDefinition::from_toml(MODELS).unwrap();
+  let ty = PrinterType::register(definitions.remove(0)).unwrap();
+
+  assert_eq!(PrinterType::from_usb(0x04f9, 0x2f00), Some(ty));
+  assert_eq!(PrinterType::from_model_code(0xf0), Some(ty));
+  assert_eq!(ty.definition().unwrap().name, "PT-REGISTRY");
+
+  let info = ty.info();
+  assert_eq!(info.max_px, 256);
+  assert_eq!(info.quirks.padding, 4);
+  assert!(info.flags.contains(PrinterFlags::HasPrecut));
+
+  // 12 mm tape and heat shrink tube report the same width, but each has
+  // its own geometry.
+  assert_eq!(ty.tape_info(TapeSize::Twelve).offset, 53);
+  assert_eq!(ty.tape_info(TapeSize::HeatShrink11_7).offset, 62);
+
+  // Tapes without geometry of their own are centred under the head.
+  let tape = ty.tape_info(TapeSize::Six);
+  assert_eq!((tape.px, tape.offset), (64, 96));
+}
+
+/// The error from loading a single printer with `fields` on top of the
+/// ones every test needs.
+fn load_error(fields: &str) -> DefinitionError {
+  let toml = format!("[[printer]]\nname = \"PT-BAD\"\n{fields}");
+
+  PrinterDefinition::from_toml(&toml).unwrap_err()
+}
+
+#[test]
+fn head_width_whole_bytes() {
+  assert!(matches!(
+    load_error("max_px = 0"),
+    DefinitionError::HeadWidth { max_px: 0, .. }
+  ));
+  assert!(matches!(
+    load_error("max_px = 100"),
+    DefinitionError::HeadWidth { max_px: 100, .. }
+  ));
+}
+
+#[test]
+fn compressed_head_too_wide() {
+  assert!(matches!(
+    load_error("max_px = 1032\nflags = [\"RasterPackBits\"]"),
+    DefinitionError::HeadTooWide { max_px: 1032, .. }
+  ));
+
+  // Uncompressed lines have room for it.
+  assert!(PrinterDefinition::from_toml("[[printer]]\nmax_px = 1032").is_ok());
+}
+
+#[test]
+fn tape_off_head() {
+  let error = load_error(r#"tapes = [{ tape = "Twelve", px = 76, offset = 60 }]"#);
+
+  assert!(matches!(
+    error,
+    DefinitionError::TapeOffHead {
+      tape: TapeSize::Twelve,
+      offset: 60,
+      px: 76,
+      max_px: 128,
+      ..
+    }
+  ));
+  assert!(error.to_string().starts_with("PT-BAD: "), "{error}");
+
+  // Large enough to wrap around rather than just run off the end.
+  assert!(matches!(
+    load_error(r#"tapes = [{ tape = "Twelve", px = 4294967295, offset = 2 }]"#),
+    DefinitionError::TapeOffHead { .. }
+  ));
+}
+
+#[test]
+fn not_toml() {
+  assert!(matches!(
+    load_error("max_px = \"wide\""),
+    DefinitionError::Toml(_)
+  ));
+}
+
+#[test]
+fn register_refuses_invalid() {
+  let definition = PrinterDefinition {
+    name: "PT-UNREGISTERED".to_string(),
+    product_id: 0xfff1,
+    max_px: 0,
+    ..Default::default()
+  };
+
+  assert!(PrinterType::register(definition).is_err());
+  assert_eq!(PrinterType::from_usb(0x04f9, 0xfff1), None);
+}
+
+#[test]
+fn custom_only_from_registry() {
+  let ty = PrinterType::register(PrinterDefinition {
+    name: "PT-SERIALISED".to_string(),
+    product_id: 0xfff2,
+    ..Default::default()
+  })
+  .unwrap();
+
+  // An index can be written out, but not read back in as a printer that
+  // might never have been registered.
+  let json = serde_json::to_string(&ty).unwrap();
+  assert!(serde_json::from_str::<PrinterType>(&json).is_err());
+  assert!(serde_json::from_str::<PrinterType>(r#"{"Custom":4000}"#).is_err());
+}
@@ -0,0 +1,54 @@
+//! The JSON the reports serialise to, which scripts depend on staying put.
+
+use ptouch_rs::{
+  PrinterDefinition,
+  PrinterFlags,
+  PrinterType,
+  Status,
+  report::{PrinterReport, StatusReport},
+};
+
+/// PT-P700, idle, with 12 mm black-on-white laminated tape loaded.
+const P700_IDLE: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x30, 0x67, 0x30, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x01, 0x00, 0x00, 0x00, 0x40,
+  0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn printer() {
+  let report = PrinterReport::from(PrinterType::PT_P700);
+
+  assert_eq!(
+    serde_json::to_string(&report).unwrap(),
+    r#"{"model":"pt_p700","vendor_id":1273,"product_id":8289,"dpi":180,"max_px":128,"flags":["raster_pack_bits","p700_init","has_precut","high_resolution"]}"#,
+  );
+}
+
+#[test]
+fn custom_printer() {
+  let ty = PrinterType::register(PrinterDefinition {
+    name: "PT-Report".to_string(),
+    product_id: 0xfff0,
+    flags: vec![PrinterFlags::RasterPackBits],
+    ..Default::default()
+  })
+  .unwrap();
+
+  let report = PrinterReport::from(ty);
+
+  assert_eq!(
+    serde_json::to_string(&report).unwrap(),
+    r#"{"model":"PT-Report","vendor_id":1273,"product_id":65520,"dpi":180,"max_px":128,"flags":["raster_pack_bits"]}"#,
+  );
+}
+
+#[test]
+fn status() {
+  let status = Status::from_request(P700_IDLE.to_vec()).expect("status block should parse");
+  let report = StatusReport::from(&status);
+
+  assert_eq!(
+    serde_json::to_string(&report).unwrap(),
+    r#"{"model_code":103,"model":"pt_p700","status":"ok","error":0,"errors":[],"media_type":"laminated","tape_width_mm":12.0,"tape_color":"white","text_color":"black","mode":["auto_cut"],"phase":"editing","notification":"none"}"#,
+  );
+}
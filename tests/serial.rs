@@ -0,0 +1,134 @@
+//! A pseudo-terminal standing in for a printer paired over Bluetooth.
+
+mod common;
+
+use std::{fs::File, io::Write, sync::mpsc, thread::JoinHandle};
+
+use ptouch_rs::{Printer, PrinterType, TapeSize};
+use rustix::pty::{OpenptFlags, grantpt, openpt, ptsname, unlockpt};
+
+/// PT-P710BT, idle, with 24 mm black-on-white laminated tape loaded.
+const P710BT_IDLE: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x30, 0x76, 0x30, 0x00, 0x00, 0x00, 0x00, 0x18, 0x01, 0x00, 0x00, 0x00, 0x40,
+  0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// Plays a printer on the controlling side of a pty until nothing has the
+/// other side open any more.
+fn fake_printer() -> (String, JoinHandle<Vec<u8>>) {
+  fake_printer_with(|port, _| port.write_all(&P710BT_IDLE).unwrap())
+}
+
+/// Like [`fake_printer`], answering status requests with `answer`.
+fn fake_printer_with(
+  answer: impl FnMut(&mut File, usize) + Send + 'static,
+) -> (String, JoinHandle<Vec<u8>>) {
+  let controller = openpt(OpenptFlags::RDWR | OpenptFlags::NOCTTY).unwrap();
+  grantpt(&controller).unwrap();
+  unlockpt(&controller).unwrap();
+
+  let path = ptsname(&controller, Vec::new())
+    .unwrap()
+    .into_string()
+    .unwrap();
+
+  let handle = std::thread::spawn(move || common::serve(File::from(controller), answer));
+
+  (path, handle)
+}
+
+#[test]
+fn open_serial_identifies_printer_from_status() {
+  let (path, handle) = fake_printer();
+
+  let printer = smol::block_on(Printer::open_serial(&path)).unwrap();
+
+  assert_eq!(printer.ty(), PrinterType::PT_P710BT);
+  assert_eq!(printer.status().media_width, TapeSize::TwentyFour);
+
+  drop(printer);
+
+  let received = handle.join().unwrap();
+
+  // Nothing beyond the invalidate, initialize and status request, all
+  // passed through untouched by the line discipline.
+  assert_eq!(common::opened(&received), b"");
+}
+
+#[test]
+fn print_over_serial() {
+  let (path, handle) = fake_printer();
+
+  let mut printer = smol::block_on(Printer::open_serial(&path)).unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 128));
+  smol::block_on(printer.print(image)).unwrap();
+
+  let status = smol::block_on(printer.reload_status()).unwrap();
+  assert_eq!(status.model, 0x76);
+
+  drop(printer);
+
+  let received = handle.join().unwrap();
+  let job = common::opened(&received);
+  let job = job
+    .strip_suffix(common::STATUS_REQUEST)
+    .expect("the status should be reloaded after the job");
+
+  // Compression, precut and advanced mode, as the P710BT takes them.
+  let header = b"M\x02M\x02\x1biM\x40\x1biK\x08";
+  let lines = common::raster_lines(job, header);
+  assert_eq!(lines.len(), 40);
+
+  // Including bytes a cooked tty would have mangled.
+  assert!(lines.iter().all(|line| line[4..] == [0xff; 16]));
+}
+
+#[test]
+fn recover_from_dropped_print() {
+  let (go, wait) = mpsc::channel::<()>();
+
+  // Stops reading after answering the status request made on opening, so
+  // the pty fills up and the first job can't be sent in full.
+  let (path, handle) = fake_printer_with(move |port, request| {
+    port.write_all(&P710BT_IDLE).unwrap();
+
+    if request == 0 {
+      let _ = wait.recv();
+    }
+  });
+
+  let printer = smol::block_on(Printer::open_serial(&path)).unwrap();
+
+  let long = image::DynamicImage::ImageLuma8(image::GrayImage::new(4000, 128));
+  let sent = smol::block_on(futures_lite::future::poll_once(printer.print(long)));
+  assert!(
+    sent.is_none(),
+    "the job should still be sending when dropped"
+  );
+
+  go.send(()).unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 128));
+  smol::block_on(printer.print(image)).unwrap();
+
+  drop(printer);
+
+  let received = handle.join().unwrap();
+  let jobs = common::opened(&received);
+
+  // Part of the first job, then the invalidate and initialize that reset
+  // the printer, then the whole of the second job.
+  let reset = [[0u8; 100].as_slice(), b"\x1b@"].concat();
+  let start = jobs
+    .windows(reset.len())
+    .position(|window| window == reset)
+    .expect("the second job should start by resetting the printer");
+  assert!(start > 0);
+
+  let header = b"M\x02M\x02\x1biM\x40\x1biK\x08";
+  assert!(jobs.starts_with(header));
+
+  let lines = common::raster_lines(&jobs[start + reset.len()..], header);
+  assert_eq!(lines.len(), 40);
+}
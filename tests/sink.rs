@@ -0,0 +1,375 @@
+//! Print jobs written out without a printer attached.
+
+use std::{
+  io::Write,
+  sync::{Arc, Mutex},
+};
+
+use ptouch_rs::{
+  Error,
+  MediaType,
+  OpenOptions,
+  PrintOptions,
+  Printer,
+  PrinterType,
+  Quirks,
+  StatusType,
+  TapeSize,
+  TwoColor,
+};
+
+/// A writer whose contents can still be read after it's handed over.
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    self.0.lock().unwrap().write(buf)
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn assumed_status() {
+  let mut printer = smol::block_on(Printer::write_to(
+    Shared::default(),
+    PrinterType::QL_800,
+    TapeSize::from_media(62, MediaType::Continuous),
+  ))
+  .unwrap();
+
+  let status = smol::block_on(printer.reload_status()).unwrap();
+
+  assert_eq!(status.printer_type(), Some(PrinterType::QL_800));
+  assert_eq!(status.media_type, MediaType::Continuous);
+  assert_eq!(status.media_width, TapeSize::Continuous(62));
+  assert_eq!(status.status_type, StatusType::Ok);
+}
+
+#[test]
+fn ql_800_invalidate() {
+  let output = Shared::default();
+
+  smol::block_on(Printer::write_to(
+    output.clone(),
+    PrinterType::QL_800,
+    TapeSize::Continuous(62),
+  ))
+  .unwrap();
+
+  // The QL-800 series wants 400 invalidate bytes rather than 100.
+  let written = output.0.lock().unwrap();
+  assert!(written[..400].iter().all(|byte| *byte == 0));
+  assert_eq!(&written[400..], b"\x1b@");
+}
+
+#[test]
+fn print_writes_whole_job() {
+  let output = Shared::default();
+
+  let printer = smol::block_on(Printer::write_to(
+    output.clone(),
+    PrinterType::PT_P700,
+    TapeSize::Twelve,
+  ))
+  .unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 76));
+  smol::block_on(printer.print(image)).unwrap();
+
+  let written = output.0.lock().unwrap();
+
+  // Invalidate and initialize, as when opening a real printer.
+  assert!(written[..100].iter().all(|byte| *byte == 0));
+  assert_eq!(&written[100..102], b"\x1b@");
+
+  // Compression, raster mode, precut and advanced mode, as the P700 takes
+  // them.
+  let job = &written[102..];
+  let header = b"M\x02\x1bia\x01M\x02\x1biM\x40\x1biK\x08";
+  assert!(job.starts_with(header));
+
+  // The 76 dot image lands on the printable area of 12 mm tape, dots 26 to
+  // 101 of the head.
+  let rows = &job[header.len()..job.len() - 1];
+  assert_eq!(rows.len(), 40 * 20);
+
+  for row in rows.chunks(20) {
+    let mut line = [0u8; 16];
+    for dot in 26..102 {
+      line[dot / 8] |= 0x80 >> (dot % 8);
+    }
+
+    assert_eq!(&row[..4], [b'G', 17, 0, 15]);
+    assert_eq!(&row[4..], line);
+  }
+
+  assert_eq!(job.last(), Some(&0x1a));
+}
+
+#[test]
+fn only_last_copy_cut() {
+  let output = Shared::default();
+
+  let printer = smol::block_on(Printer::write_to(
+    output.clone(),
+    PrinterType::PT_P700,
+    TapeSize::Twelve,
+  ))
+  .unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 76));
+  let options = PrintOptions {
+    copies: 3,
+    ..Default::default()
+  };
+  smol::block_on(printer.print_with(image, &options)).unwrap();
+
+  let written = output.0.lock().unwrap();
+
+  // Advanced mode asks for a feed and cut on the last copy only, which ends
+  // with a print and feed rather than just a print.
+  let modes = written
+    .windows(4)
+    .filter(|window| window.starts_with(b"\x1biK"))
+    .map(|window| window[3])
+    .collect::<Vec<_>>();
+  assert_eq!(modes, [0x00, 0x00, 0x08]);
+
+  let pages = written
+    .iter()
+    .filter(|byte| matches!(byte, 0x0c | 0x1a))
+    .collect::<Vec<_>>();
+  assert_eq!(pages, [&0x0c, &0x0c, &0x1a]);
+}
+
+/// The print information command sent ahead of a 40 line image.
+fn info_command(ty: PrinterType, tape: TapeSize) -> Vec<u8> {
+  let output = Shared::default();
+
+  let printer = smol::block_on(Printer::write_to(output.clone(), ty, tape)).unwrap();
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 76));
+  smol::block_on(printer.print(image)).unwrap();
+
+  let written = output.0.lock().unwrap();
+  let start = written
+    .windows(3)
+    .position(|window| window == b"\x1biz")
+    .expect("the job should have an info command");
+
+  written[start..start + 13].to_vec()
+}
+
+#[test]
+fn info_command_without_media() {
+  // 12 mm wide, 40 lines long, and the D410's trailing 0x02.
+  assert_eq!(
+    info_command(PrinterType::PT_D410, TapeSize::Twelve),
+    b"\x1biz\x00\x00\x0c\x00\x28\x00\x00\x00\x02\x00"
+  );
+}
+
+#[test]
+fn info_command_with_media() {
+  // Checked against laminated 12 mm tape, and padded to 4.4 mm at 360 dpi.
+  assert_eq!(
+    info_command(PrinterType::PT_P900, TapeSize::Twelve),
+    b"\x1biz\x86\x01\x0c\x00\x3e\x00\x00\x00\x00\x00"
+  );
+}
+
+#[test]
+fn two_color_planes() {
+  let output = Shared::default();
+
+  let printer = smol::block_on(Printer::write_to(
+    output.clone(),
+    PrinterType::QL_800,
+    TapeSize::Continuous(62),
+  ))
+  .unwrap();
+
+  // Red across one half of the 696 dot tape and black across the other.
+  let image = image::RgbImage::from_fn(150, 696, |_, y| {
+    if y < 348 {
+      image::Rgb([0xff, 0x00, 0x00])
+    } else {
+      image::Rgb([0x00, 0x00, 0x00])
+    }
+  });
+  let options = PrintOptions {
+    two_color: Some(TwoColor::default()),
+    ..Default::default()
+  };
+  smol::block_on(printer.print_with(image::DynamicImage::ImageRgb8(image), &options)).unwrap();
+
+  let written = output.0.lock().unwrap();
+  let mut job = &written[written
+    .windows(2)
+    .position(|window| window == b"w\x01")
+    .expect("the job should have two-colour raster lines")..];
+
+  // Each line is a `w` command per plane, each holding a byte count and
+  // the 90 bytes of the line, uncompressed since the QL-800 doesn't take
+  // compressed lines.
+  let mut planes = Vec::new();
+  while job[0] == b'w' {
+    let (color, len) = (job[1], job[2] as usize);
+    assert_eq!(len, 90);
+
+    planes.push((color, job[3..3 + len].to_vec()));
+    job = &job[3 + len..];
+  }
+
+  assert_eq!(planes.len(), 2 * 150);
+
+  for line in planes.chunks(2) {
+    let [(0x01, black), (0x02, red)] = line else {
+      panic!("each line should be black then red: {line:?}");
+    };
+
+    let dots = |plane: &[u8]| plane.iter().map(|byte| byte.count_ones()).sum::<u32>();
+    assert_eq!(dots(black), 348);
+    assert_eq!(dots(red), 348);
+    assert!(black.iter().zip(red).all(|(black, red)| black & red == 0));
+  }
+}
+
+#[test]
+fn two_color_needs_two_color_media() {
+  let printer = smol::block_on(Printer::write_to(
+    Shared::default(),
+    PrinterType::QL_800,
+    TapeSize::DieCut(62),
+  ))
+  .unwrap();
+
+  let image = image::DynamicImage::ImageRgb8(image::RgbImage::new(150, 696));
+  let options = PrintOptions {
+    two_color: Some(TwoColor::default()),
+    ..Default::default()
+  };
+
+  assert!(matches!(
+    smol::block_on(printer.print_with(image, &options)),
+    Err(Error::Unsupported { .. })
+  ));
+}
+
+/// The raster lines of a 40 line image printed on 12 mm tape by a P700 with
+/// `quirks`.
+fn p700_rows(quirks: Quirks) -> Vec<Vec<u8>> {
+  let output = Shared::default();
+  let options = OpenOptions {
+    quirks: Some(quirks),
+    ..Default::default()
+  };
+
+  let printer = smol::block_on(Printer::write_to_with(
+    output.clone(),
+    PrinterType::PT_P700,
+    TapeSize::Twelve,
+    options,
+  ))
+  .unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 76));
+  smol::block_on(printer.print(image)).unwrap();
+
+  let written = output.0.lock().unwrap();
+  let header = b"M\x02\x1bia\x01M\x02\x1biM\x40\x1biK\x08";
+  let rows = &written[102 + header.len()..written.len() - 1];
+
+  rows
+    .chunks(20)
+    .map(|row| {
+      assert_eq!(&row[..4], [b'G', 17, 0, 15]);
+
+      row[4..].to_vec()
+    })
+    .collect()
+}
+
+/// A line of the P700's head with `dots` printed.
+fn head_line(dots: std::ops::Range<usize>) -> Vec<u8> {
+  let mut line = vec![0u8; 16];
+  for dot in dots {
+    line[dot / 8] |= 0x80 >> (dot % 8);
+  }
+
+  line
+}
+
+#[test]
+fn padding_quirk() {
+  let rows = p700_rows(Quirks {
+    padding: 5,
+    ..Default::default()
+  });
+
+  assert_eq!(rows.len(), 5 + 40);
+  assert!(rows[..5].iter().all(|row| *row == head_line(0..0)));
+  assert!(rows[5..].iter().all(|row| *row == head_line(26..102)));
+}
+
+#[test]
+fn leading_px_quirk() {
+  // 12 mm tape would start on dot 26, so the whole image moves along to
+  // start on dot 32 rather than losing its first 6 dots.
+  let rows = p700_rows(Quirks {
+    leading_px: 32,
+    ..Default::default()
+  });
+
+  assert_eq!(rows.len(), 40);
+  assert!(rows.iter().all(|row| *row == head_line(32..108)));
+
+  let printer = smol::block_on(Printer::write_to(
+    Shared::default(),
+    PrinterType::PT_1230PC,
+    TapeSize::Twelve,
+  ))
+  .unwrap();
+  let tape = printer.tape_info();
+  assert_eq!((tape.offset, tape.px), (32, 76));
+}
+
+#[test]
+fn max_length_quirk() {
+  let printer = smol::block_on(Printer::write_to(
+    Shared::default(),
+    PrinterType::PT_D600,
+    TapeSize::Twelve,
+  ))
+  .unwrap();
+
+  // 600 lines is 84.7 mm, past the 73 mm the PT-D600 manages.
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(600, 76));
+
+  assert!(matches!(
+    smol::block_on(printer.print(image)),
+    Err(Error::LabelTooLongForPrinter { maximum: 73.0, .. })
+  ));
+}
+
+#[test]
+fn tape_wider_than_head() {
+  let output = Shared::default();
+
+  // QL tape lies across 720 dots, far past the P700's 128.
+  let printer = smol::block_on(Printer::write_to(
+    output.clone(),
+    PrinterType::PT_P700,
+    TapeSize::Continuous(62),
+  ))
+  .unwrap();
+
+  let image = image::DynamicImage::ImageLuma8(image::GrayImage::new(40, 696));
+  smol::block_on(printer.print(image)).unwrap();
+
+  let written = output.0.lock().unwrap();
+  assert!(written.windows(4).any(|window| window == [b'G', 17, 0, 15]));
+}
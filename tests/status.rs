@@ -0,0 +1,175 @@
+//! Status replies decoded byte-for-byte. Multi-byte fields are little-endian
+//! on the wire (apart from the phase number), so these have to decode the
+//! same way on any host.
+
+use enumflags2::BitFlags;
+use ptouch_rs::{
+  ErrorFlags,
+  MediaType,
+  ModeFlags,
+  Notification,
+  Phase,
+  PrinterType,
+  Status,
+  StatusField,
+  StatusType,
+  TapeColor,
+  TapeSize,
+  TextColor,
+};
+
+/// PT-P700, idle, with 12 mm black-on-white laminated tape loaded.
+const P700_IDLE: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x30, 0x67, 0x30, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x01, 0x00, 0x00, 0x00, 0x40,
+  0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x08, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// PT-P750W with its cover opened while feeding 24 mm black-on-yellow tape.
+const P750W_COVER_OPEN: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x30, 0x68, 0x30, 0x00, 0x00, 0x01, 0x10, 0x18, 0x01, 0x00, 0x00, 0x00, 0x00,
+  0x00, 0x00, 0x02, 0x00, 0x00, 0x01, 0x01, 0x00, 0x06, 0x08, 0x01, 0x02, 0x03, 0x04, 0x00, 0x00,
+];
+
+/// PT-P710BT mid-print on 18 mm white-on-black tape, mirrored.
+const P710BT_PRINTING: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x30, 0x76, 0x30, 0x00, 0x00, 0x00, 0x00, 0x12, 0x01, 0x00, 0x00, 0x00, 0xc0,
+  0x00, 0x00, 0x06, 0x01, 0x00, 0x00, 0x00, 0x00, 0x08, 0x01, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+];
+
+/// QL-800, idle, with 62 x 29 mm DK die-cut labels loaded.
+const QL800_DIE_CUT: [u8; 32] = [
+  0x80, 0x20, 0x42, 0x34, 0x38, 0x30, 0x00, 0x00, 0x00, 0x00, 0x3e, 0x0b, 0x00, 0x00, 0x00, 0x00,
+  0x00, 0x1d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+fn parse(block: &[u8]) -> Status {
+  Status::from_request(block.to_vec()).expect("status block should parse")
+}
+
+#[test]
+fn p700_idle() {
+  let status = parse(&P700_IDLE);
+
+  assert_eq!(status.model, 0x67);
+  assert_eq!(status.printer_type(), Some(PrinterType::PT_P700));
+  assert_eq!(status.error, BitFlags::empty());
+  assert!(matches!(status.media_width, TapeSize::Twelve));
+  assert_eq!(status.media_type, MediaType::Laminated);
+  assert_eq!(status.colors, 0);
+  assert_eq!(status.mode, ModeFlags::AutoCut);
+  assert_eq!(status.media_length, 0);
+  assert_eq!(status.status_type, StatusType::Ok);
+  assert_eq!(status.phase, Phase::Editing);
+  assert_eq!(status.notification, Notification::None);
+  assert_eq!(status.tape_color, TapeColor::White);
+  assert_eq!(status.text_color, TextColor::Black);
+  assert_eq!(status.hw_setting, 0x0000_0014);
+}
+
+#[test]
+fn p750w_cover_open() {
+  let status = parse(&P750W_COVER_OPEN);
+
+  assert_eq!(status.printer_type(), Some(PrinterType::PT_P750W));
+  assert_eq!(status.error, ErrorFlags::NoMedia | ErrorFlags::CoverOpen);
+  assert_eq!(status.error.bits(), 0x1001);
+  assert!(matches!(status.media_width, TapeSize::TwentyFour));
+  assert_eq!(status.mode, BitFlags::empty());
+  assert_eq!(status.status_type, StatusType::TapeDoorOpen);
+  assert_eq!(status.phase, Phase::Feeding);
+  assert_eq!(status.notification, Notification::CoverOpen);
+  assert_eq!(status.tape_color, TapeColor::Yellow);
+  assert_eq!(status.text_color, TextColor::Black);
+  assert_eq!(status.hw_setting, 0x0403_0201);
+}
+
+#[test]
+fn p710bt_printing() {
+  let status = parse(&P710BT_PRINTING);
+
+  assert_eq!(status.printer_type(), Some(PrinterType::PT_P710BT));
+  assert!(matches!(status.media_width, TapeSize::Eighteen));
+  assert_eq!(status.mode, ModeFlags::AutoCut | ModeFlags::MirrorPrinting);
+  assert_eq!(status.status_type, StatusType::PhaseChange);
+  assert_eq!(status.phase, Phase::Printing);
+  assert_eq!(status.tape_color, TapeColor::Black);
+  assert_eq!(status.text_color, TextColor::White);
+  assert_eq!(status.hw_setting, 0x8000_0000);
+}
+
+#[test]
+fn wrong_print_head_mark() {
+  let mut block = P700_IDLE;
+  block[0] = 0x81;
+
+  let Err(ptouch_rs::Error::StatusParse(err)) = Status::from_request(block.to_vec()) else {
+    panic!("status block should be rejected");
+  };
+
+  assert_eq!(err.offset, 0);
+  assert_eq!(err.field, StatusField::PrintHeadMark);
+  assert_eq!(err.actual, [0x81]);
+  assert_eq!(err.raw, block);
+}
+
+#[test]
+fn truncated() {
+  let Err(ptouch_rs::Error::StatusParse(err)) = Status::from_request(P700_IDLE[..26].to_vec())
+  else {
+    panic!("status block should be rejected");
+  };
+
+  assert_eq!(err.offset, 26);
+  assert_eq!(err.field, StatusField::HardwareSettings);
+  assert!(err.actual.is_empty());
+}
+
+#[test]
+fn trailing_bytes() {
+  let mut block = P700_IDLE.to_vec();
+  block.push(0xff);
+
+  let Err(ptouch_rs::Error::StatusParse(err)) = Status::from_request(block) else {
+    panic!("status block should be rejected");
+  };
+
+  assert_eq!(err.offset, 32);
+  assert_eq!(err.field, StatusField::End);
+  assert_eq!(err.actual, [0xff]);
+}
+
+#[test]
+fn heat_shrink_widths() {
+  let mut block = P700_IDLE;
+  block[10] = 11;
+  block[11] = 0x17;
+
+  let status = parse(&block);
+
+  assert_eq!(status.media_type, MediaType::HeatShrink3To1);
+  assert_eq!(status.media_width, TapeSize::HeatShrink11_2);
+
+  // 11 mm isn't a width of regular tape.
+  block[11] = 0x01;
+
+  let status = parse(&block);
+
+  assert_eq!(status.media_type, MediaType::Laminated);
+  assert_eq!(status.media_width, TapeSize::Unknown(11));
+}
+
+#[test]
+fn ql800_die_cut() {
+  let status = parse(&QL800_DIE_CUT);
+
+  assert_eq!(status.printer_type(), Some(PrinterType::QL_800));
+  assert_eq!(status.media_type, MediaType::DieCut);
+  assert_eq!(status.media_width, TapeSize::DieCut(62));
+  assert_eq!(status.media_length, 29);
+  assert_eq!(status.status_type, StatusType::Ok);
+
+  let tape = PrinterType::QL_800.tape_info(status.media_width);
+
+  assert_eq!(tape.px, 696);
+  assert_eq!(tape.offset, 12);
+}
@@ -0,0 +1,164 @@
+//! Estimates of tape used by a job, and the counter that keeps a running
+//! total of them.
+
+use std::path::PathBuf;
+
+use image::{DynamicImage, GrayImage};
+use ptouch_rs::{PrintOptions, PrinterType, Quirks, TapeCounter, TapeUsage};
+
+/// An image 100 lines long, which prints 14.1 mm of label at 180 dpi.
+fn image() -> DynamicImage {
+  DynamicImage::ImageLuma8(GrayImage::new(100, 76))
+}
+
+fn estimate(ty: PrinterType, copies: u32, chain: bool, precut: bool) -> TapeUsage {
+  let options = PrintOptions {
+    copies,
+    chain,
+    precut,
+    ..Default::default()
+  };
+
+  TapeUsage::estimate(ty, &[image()], &options).unwrap()
+}
+
+fn assert_mm(actual: f32, expected: f32) {
+  assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+}
+
+#[test]
+fn single_label() {
+  let usage = estimate(PrinterType::PT_P700, 1, false, true);
+
+  assert_eq!(usage.labels, 1);
+  assert_mm(usage.printed, 14.11);
+  // The leader to bring the label to the cutter, and the margin at the
+  // cut.
+  assert_mm(usage.waste, 25.4 + 2.0);
+}
+
+#[test]
+fn copies_cut_apart() {
+  let usage = estimate(PrinterType::PT_P700, 3, false, true);
+
+  assert_eq!(usage.labels, 3);
+  assert_mm(usage.printed, 3.0 * 14.11);
+  assert_mm(usage.waste, 25.4 + 3.0 * 2.0);
+}
+
+#[test]
+fn copies_left_joined() {
+  // Without precut the copies stay in one strip with a single cut.
+  let usage = estimate(PrinterType::PT_P700, 3, false, false);
+  assert_mm(usage.waste, 25.4 + 2.0);
+
+  // The PT-2420PC can't cut between labels however it's asked.
+  let usage = estimate(PrinterType::PT_2420PC, 3, false, true);
+  assert_mm(usage.waste, 25.4 + 2.0);
+}
+
+#[test]
+fn chained() {
+  // Nothing is fed out after the last label, so only the cuts between
+  // copies are wasted.
+  let usage = estimate(PrinterType::PT_P700, 3, true, true);
+  assert_mm(usage.waste, 2.0 * 2.0);
+
+  let usage = estimate(PrinterType::PT_P700, 1, true, true);
+  assert_mm(usage.waste, 0.0);
+}
+
+#[test]
+fn each_image_is_a_job() {
+  let options = PrintOptions::default();
+  let usage = TapeUsage::estimate(PrinterType::PT_P700, &[image(), image()], &options).unwrap();
+
+  assert_eq!(usage.labels, 2);
+  assert_mm(usage.waste, 2.0 * (25.4 + 2.0));
+}
+
+#[test]
+fn per_printer_feed() {
+  let usage = estimate(PrinterType::QL_800, 1, false, true);
+
+  // At least 12.7 mm of label, and a short feed to the cutter.
+  assert_mm(usage.printed, 12.7);
+  assert_mm(usage.waste, 3.0 + 3.0);
+}
+
+#[test]
+fn quirks() {
+  let options = PrintOptions::default();
+
+  // The PT-2730 sends 48 blank lines ahead of every label.
+  let usage = TapeUsage::estimate(PrinterType::PT_2730, &[image()], &options).unwrap();
+  assert_mm(usage.printed, 148.0 * 25.4 / 180.0);
+
+  let usage = TapeUsage::estimate_with(
+    PrinterType::PT_2730,
+    Quirks::default(),
+    &[image()],
+    &options,
+  )
+  .unwrap();
+  assert_mm(usage.printed, 14.11);
+}
+
+/// A path in the temporary directory no other test uses.
+fn counter_path(name: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(format!("ptouch-{name}-{}", std::process::id()));
+  let _ = std::fs::remove_file(&path);
+
+  path
+}
+
+#[test]
+fn counter_round_trip() {
+  let path = counter_path("round-trip");
+  let usage = estimate(PrinterType::PT_P700, 1, false, true);
+
+  let mut counter = TapeCounter::open(&path).unwrap();
+  assert_eq!(counter.get("A"), 0.0);
+
+  counter.record("A", &usage).unwrap();
+  counter.record("B", &usage).unwrap();
+  counter.record("A", &usage).unwrap();
+
+  let counter = TapeCounter::open(&path).unwrap();
+  assert_mm(counter.get("A"), 2.0 * usage.total());
+  assert_mm(counter.get("B"), usage.total());
+  assert_eq!(
+    counter.iter().map(|(serial, _)| serial).collect::<Vec<_>>(),
+    ["A", "B"]
+  );
+}
+
+#[test]
+fn counter_concurrent_records() {
+  let path = counter_path("concurrent");
+  let usage = TapeUsage {
+    labels: 1,
+    printed: 1.0,
+    waste: 0.0,
+  };
+
+  // Each thread opens the counter before the others have written to it,
+  // as separate runs of the CLI would.
+  let threads = (0..8)
+    .map(|_| {
+      let mut counter = TapeCounter::open(&path).unwrap();
+
+      std::thread::spawn(move || {
+        for _ in 0..10 {
+          counter.record("A", &usage).unwrap();
+        }
+      })
+    })
+    .collect::<Vec<_>>();
+
+  for thread in threads {
+    thread.join().unwrap();
+  }
+
+  assert_eq!(TapeCounter::open(&path).unwrap().get("A"), 80.0);
+}